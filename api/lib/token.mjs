@@ -0,0 +1,27 @@
+import crypto from 'crypto';
+
+// Signed license tokens the desktop app can verify offline.
+// Format: v1.<base64url(JSON claims)>.<base64url(Ed25519 signature of "v1.<claims>")>
+// LICENSE_SIGNING_KEY is the Ed25519 private key as PKCS#8 PEM; generate a pair with
+// `node scripts/license-keypair.mjs` and build the app with the matching public key.
+
+const TOKEN_TTL_SECONDS = 14 * 24 * 60 * 60;
+
+let signingKey;
+
+function getSigningKey() {
+  if (!process.env.LICENSE_SIGNING_KEY) return null;
+  if (!signingKey) signingKey = crypto.createPrivateKey(process.env.LICENSE_SIGNING_KEY.replace(/\\n/g, '\n'));
+  return signingKey;
+}
+
+export function signLicenseToken(key, plan = 'standard') {
+  const privateKey = getSigningKey();
+  if (!privateKey) return null;
+
+  const iat = Math.floor(Date.now() / 1000);
+  const claims = { ver: 1, key: key.trim().toUpperCase(), plan: plan || 'standard', iat, exp: iat + TOKEN_TTL_SECONDS };
+  const signingInput = `v1.${Buffer.from(JSON.stringify(claims)).toString('base64url')}`;
+  const signature = crypto.sign(null, Buffer.from(signingInput), privateKey);
+  return `${signingInput}.${signature.toString('base64url')}`;
+}
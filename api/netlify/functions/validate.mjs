@@ -1,4 +1,5 @@
 import { validateKey } from '../../lib/keys.mjs';
+import { signLicenseToken } from '../../lib/token.mjs';
 
 const CORS_HEADERS = {
   'Access-Control-Allow-Origin': '*',
@@ -22,6 +23,9 @@ export default async (req) => {
     }
 
     const result = await validateKey(key);
+    if (result.valid) {
+      result.token = signLicenseToken(key, result.plan);
+    }
     return Response.json(result, { headers: CORS_HEADERS });
   } catch (e) {
     return Response.json({ valid: false, reason: 'Server error' }, { status: 500, headers: CORS_HEADERS });
@@ -0,0 +1,12 @@
+// Generate the Ed25519 key pair for license tokens.
+// The private key goes in the API's LICENSE_SIGNING_KEY env var; the public key is
+// compiled into the desktop app via LICENSE_PUBLIC_KEY at build time.
+import crypto from 'crypto';
+
+const { publicKey, privateKey } = crypto.generateKeyPairSync('ed25519');
+const rawPublic = publicKey.export({ format: 'der', type: 'spki' }).subarray(-32);
+
+console.log('LICENSE_SIGNING_KEY (API env, keep secret):');
+console.log(privateKey.export({ format: 'pem', type: 'pkcs8' }));
+console.log('LICENSE_PUBLIC_KEY (app build env):');
+console.log(rawPublic.toString('hex'));
@@ -13,10 +13,32 @@ import fs from 'fs';
 import path from 'path';
 import { homedir } from 'os';
 import { createInterface } from 'readline';
+import { fetch, EnvHttpProxyAgent } from 'undici';
 
-const DATA_DIR = path.join(homedir(), '.config', 'hl-signalbot');
-const LICENSE_FILE = path.join(DATA_DIR, 'license.key');
-const LICENSE_API = 'https://hlsignalbot.netlify.app/api/validate';
+// The desktop app's config dir (Rust's dirs::config_dir()), so both share one license
+function configDir() {
+  if (process.platform === 'win32') return process.env.APPDATA || path.join(homedir(), 'AppData', 'Roaming');
+  if (process.platform === 'darwin') return path.join(homedir(), 'Library', 'Application Support');
+  const xdg = process.env.XDG_CONFIG_HOME;
+  return xdg && path.isAbsolute(xdg) ? xdg : path.join(homedir(), '.config');
+}
+
+const DATA_DIR = process.env.DATA_DIR || path.join(configDir(), 'hl-signalbot');
+// Shared with the desktop app, which keeps its validation state next to the key
+const LICENSE_FILE = path.join(DATA_DIR, 'license.json');
+// Where earlier CLI versions kept the bare key
+const LEGACY_LICENSE_FILE = path.join(DATA_DIR, 'license.key');
+const SETTINGS_FILE = path.join(DATA_DIR, 'app-settings.json');
+// Same defaults as the desktop app; override with "license" in app-settings.json
+const LICENSE_DEFAULTS = {
+  endpoints: ['https://c13studios.com/api/validate', 'https://hlsignalbot.netlify.app/api/validate'],
+  timeoutSecs: 5,
+  retries: 1,
+  retryDelayMs: 500,
+};
+// Offline use allowed without a successful online check, as in the app. The CLI can't
+// verify the app's signed tokens, so for it every offline start counts against this.
+const GRACE_DAYS = 7;
 
 function parseArgs(argv){
   const out = { config: null };
@@ -62,34 +84,119 @@ function validKeyFormat(key) {
     && k.split('-').slice(1).every(s => s.length === 4 && /^[A-Z0-9]+$/.test(s));
 }
 
-async function validateOnline(key) {
+function licenseSettings() {
   try {
-    const res = await fetch(LICENSE_API, {
-      method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ key: key.trim().toUpperCase() }),
-      signal: AbortSignal.timeout(5000),
-    });
-    const data = await res.json();
-    return data.valid === true;
+    const saved = JSON.parse(fs.readFileSync(SETTINGS_FILE, 'utf-8')).license || {};
+    const settings = { ...LICENSE_DEFAULTS, ...saved };
+    if (!Array.isArray(settings.endpoints) || settings.endpoints.length === 0) settings.endpoints = LICENSE_DEFAULTS.endpoints;
+    return settings;
   } catch {
-    return null;
+    return LICENSE_DEFAULTS;
+  }
+}
+
+// Same rules as the app: the configured proxy with its noProxy hosts, otherwise
+// HTTP_PROXY/HTTPS_PROXY/NO_PROXY from the environment
+function licenseDispatcher({ proxy, noProxy }) {
+  if (!proxy) return new EnvHttpProxyAgent();
+  return new EnvHttpProxyAgent({ httpProxy: proxy, httpsProxy: proxy, noProxy: noProxy || '' });
+}
+
+// The server's answer ({ valid, token?, plan? }) once an endpoint answers; null if none could be reached
+async function validateOnline(key) {
+  const settings = licenseSettings();
+  const { endpoints, timeoutSecs, retries, retryDelayMs } = settings;
+  const dispatcher = licenseDispatcher(settings);
+  for (const endpoint of endpoints) {
+    for (let attempt = 0; attempt <= retries; attempt++) {
+      if (attempt > 0) await new Promise(r => setTimeout(r, retryDelayMs * 2 ** (attempt - 1)));
+      try {
+        const res = await fetch(endpoint, {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify({ key: key.trim().toUpperCase() }),
+          signal: AbortSignal.timeout(timeoutSecs * 1000),
+          dispatcher,
+        });
+        if (res.status >= 500) continue;
+        const data = await res.json();
+        if (typeof data.valid === 'boolean') return data;
+      } catch {}
+    }
   }
+  return null;
+}
+
+function readLicenseFile() {
+  try { return JSON.parse(fs.readFileSync(LICENSE_FILE, 'utf-8')) || {}; } catch { return {}; }
+}
+
+function writeLicenseFile(data) {
+  fs.writeFileSync(LICENSE_FILE, JSON.stringify(data, null, 2), { mode: 0o600 });
+  try { fs.unlinkSync(LEGACY_LICENSE_FILE); } catch {}
+}
+
+// Drop a rejected key; the app's other fields in license.json are kept
+function saveLicenseKey(key) {
+  writeLicenseFile({ ...readLicenseFile(), key });
+}
+
+// What the app records after an accepted online check, which also ends any grace period
+function saveAcceptedLicense(key, answer) {
+  writeLicenseFile({
+    key,
+    token: typeof answer.token === 'string' ? answer.token : null,
+    plan: typeof answer.plan === 'string' ? answer.plan : null,
+    validatedAt: new Date().toISOString(),
+  });
+}
+
+function savedLicenseKey() {
+  const key = readLicenseFile().key;
+  if (key) return String(key).trim();
+  try { return fs.readFileSync(LEGACY_LICENSE_FILE, 'utf-8').trim(); } catch { return null; }
+}
+
+// Whether `key` may run without reaching the license server. Uses the grace period
+// shared with the app, starting it on the first unverified start.
+function allowedOffline(key) {
+  const stored = readLicenseFile();
+  // Like the app, a different key starts from a clean record but keeps the grace period
+  const data = stored.key === key ? stored : { key, graceStartedAt: stored.graceStartedAt };
+  if (data.revokedAt) {
+    console.log(`\x1b[31m✗ This license was revoked (${data.revokedReason || 'no reason given'}).\x1b[0m`);
+    return false;
+  }
+  const now = Date.now();
+  let started = Date.parse(data.graceStartedAt);
+  if (Number.isNaN(started)) {
+    started = now;
+    data.graceStartedAt = new Date(now).toISOString();
+  }
+  const until = new Date(started + GRACE_DAYS * 24 * 60 * 60 * 1000);
+  if (now >= until.getTime()) {
+    console.log(`\x1b[31m✗ The license server could not be reached and the offline grace period ended on ${until.toISOString().slice(0, 10)}. Connect to the internet to verify your license.\x1b[0m`);
+    return false;
+  }
+  writeLicenseFile(data);
+  console.log(`\x1b[33m! The license server could not be reached. Running unverified until ${until.toISOString().replace('T', ' ').slice(0, 16)} UTC.\x1b[0m`);
+  return true;
 }
 
 async function checkLicense() {
   if (!fs.existsSync(DATA_DIR)) fs.mkdirSync(DATA_DIR, { recursive: true });
 
-  if (fs.existsSync(LICENSE_FILE)) {
-    const saved = fs.readFileSync(LICENSE_FILE, 'utf-8').trim();
-    if (validKeyFormat(saved)) {
-      const online = await validateOnline(saved);
-      if (online === true || (online === null && validKeyFormat(saved))) return true;
-      if (online === false) {
-        console.log('\x1b[31m✗ License key is no longer valid.\x1b[0m');
-        fs.unlinkSync(LICENSE_FILE);
-      }
+  const saved = savedLicenseKey();
+  if (saved && validKeyFormat(saved)) {
+    const key = saved.toUpperCase();
+    const online = await validateOnline(key);
+    if (online === null) return allowedOffline(key);
+    if (online.valid) {
+      saveAcceptedLicense(key, online);
+      return true;
     }
+    console.log('\x1b[31m✗ License key is no longer valid.\x1b[0m');
+    saveLicenseKey(null);
   }
 
   console.log('\n\x1b[36m╔══════════════════════════════════════════╗');
@@ -106,13 +213,15 @@ async function checkLicense() {
       continue;
     }
 
-    const online = await validateOnline(key);
-    if (online === false) {
+    const normalized = key.trim().toUpperCase();
+    const online = await validateOnline(normalized);
+    if (online === null) return allowedOffline(normalized);
+    if (!online.valid) {
       console.log('  \x1b[31m✗ Invalid license key. Please check and try again.\x1b[0m\n');
       continue;
     }
 
-    fs.writeFileSync(LICENSE_FILE, key.trim().toUpperCase(), { mode: 0o600 });
+    saveAcceptedLicense(normalized, online);
     console.log('  \x1b[32m✓ License activated!\x1b[0m\n');
     return true;
   }
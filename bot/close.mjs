@@ -35,7 +35,16 @@ const TRADE_LOG = process.env.TRADE_LOG || path.join(DATA_DIR, 'trades.jsonl');
 
 if (process.env.HL_WALLET_ADDRESS) cfg.wallet.address = String(process.env.HL_WALLET_ADDRESS).trim();
 
+// The desktop app passes the key over stdin (HL_KEY_FROM_STDIN=1) so it never sits
+// in the process environment, where /proc/<pid>/environ would expose it.
+function readKeyFromStdin() {
+  if (process.env.HL_KEY_FROM_STDIN !== '1') return null;
+  delete process.env.HL_KEY_FROM_STDIN;
+  try { return fs.readFileSync(0, 'utf8').trim() || null; } catch { return null; }
+}
+
 const pk = (
+  readKeyFromStdin() ||
   (process.env.HL_PRIVATE_KEY && String(process.env.HL_PRIVATE_KEY).trim()) ||
   (process.env.HL_PRIVATE_KEY_PATH && readSecret(String(process.env.HL_PRIVATE_KEY_PATH).trim())) ||
   (cfg?.wallet?.privateKeyPath && readSecret(String(cfg.wallet.privateKeyPath).trim())) ||
@@ -164,7 +164,16 @@ function readSecretFromPath(p){
   try { return fs.readFileSync(expandHome(p), 'utf8').trim(); } catch { return null; }
 }
 
+// The desktop app passes the key over stdin (HL_KEY_FROM_STDIN=1) so it never sits
+// in the process environment, where /proc/<pid>/environ would expose it.
+function readKeyFromStdin() {
+  if (process.env.HL_KEY_FROM_STDIN !== '1') return null;
+  delete process.env.HL_KEY_FROM_STDIN;
+  try { return fs.readFileSync(0, 'utf8').trim() || null; } catch { return null; }
+}
+
 const pk = (
+  readKeyFromStdin() ||
   (process.env.HL_PRIVATE_KEY && String(process.env.HL_PRIVATE_KEY).trim()) ||
   (process.env.HL_PRIVATE_KEY_PATH && readSecretFromPath(String(process.env.HL_PRIVATE_KEY_PATH).trim())) ||
   (cfg?.wallet?.privateKeyPath && readSecretFromPath(String(cfg.wallet.privateKeyPath).trim())) ||
@@ -333,6 +342,7 @@ function persistState(){
         exitsPlacedForPosKey: state.exitsPlacedForPosKey,
         lastExitAtMs: state.lastExitAtMs,
         activeSide: state.activeSide,
+        busy: state.busy,
         entryPx: state.entryPx,
         entryNotionalUsd: state.entryNotionalUsd,
         initialSz: state.initialSz,
@@ -363,6 +373,10 @@ const state = {
   exitsPlacedForPosKey: null,
   lastExitAtMs: 0,
 
+  // order flow in progress ('entering' from the entry order until TP/SL are placed);
+  // the app only restarts the bot when this and activeSide are both null
+  busy: null,
+
   // position plan
   activeSide: null,
   entryPx: null,
@@ -389,6 +403,8 @@ const state = {
 
 const loaded = loadState();
 if (loaded && typeof loaded === 'object') Object.assign(state, loaded);
+// Whatever the last process was doing died with it
+state.busy = null;
 // Back-compat: if we previously persisted only `halted: true`, attach today's UTC day key
 // so the auto-reset-at-midnight logic works immediately.
 if (state.halted && !state.haltDayUtc) {
@@ -1346,6 +1362,19 @@ async function tryEnter(){
 
   console.log(nowIso(), 'Signal', sig.side, 'enter notional', cappedNotional.toFixed(2), 'stopPct', sig.stopPct.toFixed(4), sig.reason);
   tauriEmit({ type: 'signal', side: sig.side, reason: sig.reason, notional: cappedNotional, stopPct: sig.stopPct });
+  // Mark the entry before any order goes out: until TP/SL are placed the position is
+  // not yet in activeSide, and the app must not take this for flat.
+  state.busy = 'entering';
+  persistState();
+  try {
+    await enterPosition(sig, sz, priceNow);
+  } finally {
+    state.busy = null;
+    persistState();
+  }
+}
+
+async function enterPosition(sig, sz, priceNow){
   await ensureLeverage();
   const resp = await placeMarket(sig.side, sz);
 
@@ -1,3 +1,70 @@
+use std::path::Path;
+use sha2::{Digest, Sha256};
+
 fn main() {
+  write_bot_manifest();
+  check_license_key();
   tauri_build::build()
 }
+
+/// Release builds must be able to verify license tokens, so LICENSE_PUBLIC_KEY has to
+/// be set to a hex-encoded Ed25519 public key. See license.rs.
+fn check_license_key() {
+  println!("cargo:rerun-if-env-changed=LICENSE_PUBLIC_KEY");
+  if std::env::var("PROFILE").as_deref() != Ok("release") {
+    return;
+  }
+  match std::env::var("LICENSE_PUBLIC_KEY") {
+    Ok(key) if key.trim().len() == 64 && key.trim().chars().all(|c| c.is_ascii_hexdigit()) => {}
+    Ok(_) => panic!("LICENSE_PUBLIC_KEY must be a 64-character hex Ed25519 public key"),
+    Err(_) => panic!("LICENSE_PUBLIC_KEY must be set for release builds"),
+  }
+}
+
+/// Embed the SHA-256 of every bundled bot script (the `.mjs`/`.json` resources under
+/// `bot/`) and of the bundled node_modules tarball, so the app can verify its runtime
+/// copy before running it. See integrity.rs. Release builds must bundle the tarball.
+fn write_bot_manifest() {
+  let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+  let conf_path = Path::new(&manifest_dir).join("tauri.conf.json");
+  println!("cargo:rerun-if-changed={}", conf_path.display());
+  let conf: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(&conf_path).expect("read tauri.conf.json")).expect("parse tauri.conf.json");
+
+  let release = std::env::var("PROFILE").as_deref() == Ok("release");
+  let sha256 = |bytes: &[u8]| -> String { Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect() };
+
+  let mut entries: Vec<(String, String)> = Vec::new();
+  let mut modules: Option<String> = None;
+  for (src, dest) in conf["bundle"]["resources"].as_object().into_iter().flatten() {
+    let Some(name) = dest.as_str().and_then(|d| d.strip_prefix("bot/")) else { continue };
+    let path = Path::new(&manifest_dir).join(src);
+    println!("cargo:rerun-if-changed={}", path.display());
+    if name == "node_modules.tar.gz" {
+      // Only built by CI; dev builds run the bot from the project's own bot/
+      match std::fs::read(&path) {
+        Ok(bytes) => modules = Some(sha256(&bytes)),
+        Err(e) if release => panic!("read {}: {} (release builds must bundle node_modules)", path.display(), e),
+        Err(_) => {}
+      }
+      continue;
+    }
+    if !(name.ends_with(".mjs") || name.ends_with(".json")) {
+      continue;
+    }
+    let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+    entries.push((name.to_string(), sha256(&bytes)));
+  }
+  entries.sort();
+
+  let body: String = entries.iter().map(|(name, hash)| format!("    ({:?}, {:?}),\n", name, hash)).collect();
+  let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("bot_manifest.rs");
+  std::fs::write(
+    out,
+    format!(
+      "pub const BOT_MANIFEST: &[(&str, &str)] = &[\n{}];\npub const BOT_MODULES_SHA256: Option<&str> = {:?};\n",
+      body, modules
+    ),
+  )
+  .expect("write bot manifest");
+}
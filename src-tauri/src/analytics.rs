@@ -0,0 +1,127 @@
+use chrono::{Datelike, TimeZone, Timelike};
+use serde::Serialize;
+use crate::trades::{self, RoundTrip};
+
+// --- Performance Breakdown ---
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bucket {
+    pub key: String,
+    pub trades: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pnl_usd: f64,
+    pub avg_pnl_usd: Option<f64>,
+    pub win_rate: Option<f64>,
+}
+
+impl Bucket {
+    fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into(), trades: 0, wins: 0, losses: 0, pnl_usd: 0.0, avg_pnl_usd: None, win_rate: None }
+    }
+
+    fn add(&mut self, trip: &RoundTrip) {
+        self.trades += 1;
+        self.pnl_usd += trip.pnl_usd;
+        if trip.pnl_usd > 0.0 {
+            self.wins += 1;
+        } else if trip.pnl_usd < 0.0 {
+            self.losses += 1;
+        }
+        self.avg_pnl_usd = Some(self.pnl_usd / self.trades as f64);
+        let decided = self.wins + self.losses;
+        self.win_rate = if decided > 0 { Some(self.wins as f64 / decided as f64) } else { None };
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceBreakdown {
+    /// IANA zone the hour and weekday buckets were computed in.
+    pub timezone: String,
+    pub total: Bucket,
+    pub by_hour: Vec<Bucket>,
+    pub by_weekday: Vec<Bucket>,
+    pub by_side: Vec<Bucket>,
+    pub by_exit_reason: Vec<Bucket>,
+}
+
+/// `display.timezone` from config.json, falling back to UTC when unset or unknown.
+fn display_timezone(config_dir: &std::path::Path) -> chrono_tz::Tz {
+    let name = std::fs::read_to_string(config_dir.join("config.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|cfg| cfg["display"]["timezone"].as_str().map(str::to_string));
+    match name {
+        Some(n) => n.parse().unwrap_or_else(|_| {
+            log::warn!("Unknown display.timezone '{}' — using UTC", n);
+            chrono_tz::UTC
+        }),
+        None => chrono_tz::UTC,
+    }
+}
+
+fn breakdown(trips: &[RoundTrip], tz: chrono_tz::Tz) -> PerformanceBreakdown {
+    let mut total = Bucket::new("all");
+    let mut by_hour: Vec<Bucket> = (0..24).map(|h| Bucket::new(format!("{:02}", h))).collect();
+    let mut by_weekday: Vec<Bucket> = WEEKDAYS.iter().map(|d| Bucket::new(*d)).collect();
+    let mut by_side: Vec<Bucket> = vec![Bucket::new("long"), Bucket::new("short")];
+    let mut by_exit_reason: Vec<Bucket> = Vec::new();
+
+    for trip in trips {
+        total.add(trip);
+
+        // Sessions are judged by when the trade was entered
+        if let Some(at) = tz.timestamp_millis_opt(trip.entry_ts_ms()).single() {
+            by_hour[at.hour() as usize].add(trip);
+            by_weekday[at.weekday().num_days_from_monday() as usize].add(trip);
+        }
+
+        if let Some(side) = trip.side {
+            let key = side.as_str();
+            if let Some(b) = by_side.iter_mut().find(|b| b.key == key) {
+                b.add(trip);
+            }
+        }
+
+        match by_exit_reason.iter_mut().find(|b| b.key == trip.exit_reason) {
+            Some(b) => b.add(trip),
+            None => {
+                let mut b = Bucket::new(trip.exit_reason.clone());
+                b.add(trip);
+                by_exit_reason.push(b);
+            }
+        }
+    }
+    by_exit_reason.sort_by(|a, b| a.pnl_usd.total_cmp(&b.pnl_usd));
+
+    PerformanceBreakdown {
+        timezone: tz.name().to_string(),
+        total,
+        by_hour,
+        by_weekday,
+        by_side,
+        by_exit_reason,
+    }
+}
+
+/// PnL, trade count and win rate of completed trades bucketed by entry hour and
+/// weekday (in `display.timezone`), side and close reason. `from`/`to` are epoch ms
+/// bounds on the close time.
+#[tauri::command]
+pub async fn get_performance_breakdown(
+    app: tauri::AppHandle,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<PerformanceBreakdown, String> {
+    let config_dir = crate::bot_config_dir(&app)?;
+    let records = trades::load_trade_log(&config_dir)?;
+    let trips: Vec<RoundTrip> = trades::round_trips(&records)
+        .into_iter()
+        .filter(|t| from.map_or(true, |f| t.close_ts_ms >= f) && to.map_or(true, |end| t.close_ts_ms < end))
+        .collect();
+    Ok(breakdown(&trips, display_timezone(&config_dir)))
+}
@@ -0,0 +1,361 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, State};
+
+// --- Audit Log ---
+//
+// Append-only record of privileged actions: starting, stopping and restarting the
+// bot, closing positions, and every write to config, profiles, secrets or app
+// settings. Each line of audit.jsonl carries the hash of the entry before it, so
+// editing, reordering or deleting an entry breaks the chain from that point on. This
+// is tamper-evident, not tamper-proof: cutting entries off the end, or rewriting the
+// whole file and its chain, can't be detected from the file alone.
+//
+// Logging never blocks the action itself; a failed append is only logged.
+
+const AUDIT_FILE: &str = "audit.jsonl";
+/// `prevHash` of the first entry.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Placeholder for secret parameters.
+pub const REDACTED: &str = "[REDACTED]";
+/// Actor for commands invoked from the app's UI.
+pub const USER: &str = "user";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Ok,
+    Error,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub ts: String,
+    pub action: String,
+    /// `user` for UI commands, otherwise the background task that acted.
+    pub actor: String,
+    pub os_user: Option<String>,
+    pub params: serde_json::Value,
+    pub outcome: Outcome,
+    pub error: Option<String>,
+    pub app_version: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// SHA-256 over the canonical JSON of every field except `hash`.
+    fn compute_hash(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(map) = value.as_object_mut() {
+            map.remove("hash");
+        }
+        crate::sha256_hex(crate::canonical_json(&value).as_bytes())
+    }
+}
+
+/// Managed state: where the log lives and the tail of the chain.
+pub struct AuditLog {
+    path: PathBuf,
+    app_version: String,
+    /// (seq, hash) of the last entry, read from the file on first use.
+    head: Mutex<Option<(u64, String)>>,
+}
+
+impl AuditLog {
+    pub fn new(dir: &Path, app_version: String) -> Self {
+        Self { path: dir.join(AUDIT_FILE), app_version, head: Mutex::new(None) }
+    }
+
+    /// Last entry's position in the chain, or the genesis values for a new log.
+    fn read_head(&self) -> (u64, String) {
+        let raw = std::fs::read_to_string(&self.path).unwrap_or_default();
+        let Some(last) = raw.lines().rev().find(|l| !l.trim().is_empty()) else {
+            return (0, GENESIS_HASH.to_string());
+        };
+        match serde_json::from_str::<AuditEntry>(last) {
+            Ok(entry) => (entry.seq, entry.hash),
+            Err(e) => {
+                // Chain on from the raw line; verify_audit_log will still flag it.
+                log::warn!("Last {} entry is unreadable: {}", AUDIT_FILE, e);
+                (raw.lines().count() as u64, crate::sha256_hex(last.as_bytes()))
+            }
+        }
+    }
+
+    fn append(&self, actor: &str, action: &str, params: serde_json::Value, error: Option<String>) -> Result<(), String> {
+        let mut head = self.head.lock().unwrap();
+        let (seq, prev_hash) = head.take().unwrap_or_else(|| self.read_head());
+        let mut entry = AuditEntry {
+            seq: seq + 1,
+            ts: Utc::now().to_rfc3339(),
+            action: action.to_string(),
+            actor: actor.to_string(),
+            os_user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok(),
+            params,
+            outcome: if error.is_some() { Outcome::Error } else { Outcome::Ok },
+            error,
+            app_version: self.app_version.clone(),
+            prev_hash: prev_hash.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+
+        let mut opts = std::fs::OpenOptions::new();
+        opts.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+        let written = opts.open(&self.path).and_then(|mut f| {
+            f.write_all(format!("{}\n", line).as_bytes())?;
+            f.sync_data()
+        });
+        match written {
+            Ok(()) => {
+                *head = Some((entry.seq, entry.hash));
+                Ok(())
+            }
+            Err(e) => {
+                *head = Some((seq, prev_hash));
+                Err(format!("Failed to append to {}: {}", self.path.display(), e))
+            }
+        }
+    }
+}
+
+/// Record `action` by `actor` with the outcome of `result`, and hand `result` back.
+/// Secret values in `params` and the error are masked before they are written.
+pub fn record_as<T, E: std::fmt::Display>(
+    app: &tauri::AppHandle,
+    actor: &str,
+    action: &str,
+    params: serde_json::Value,
+    result: Result<T, E>,
+) -> Result<T, E> {
+    let Some(audit) = app.try_state::<AuditLog>() else { return result };
+    let redactor = audit.path.parent().map(crate::redact::Redactor::load).unwrap_or_default();
+    let params_text = crate::canonical_json(&params);
+    let params = match redactor.apply(&params_text) {
+        std::borrow::Cow::Borrowed(_) => params,
+        std::borrow::Cow::Owned(masked) => serde_json::from_str(&masked).unwrap_or(serde_json::Value::String(masked)),
+    };
+    let error = result.as_ref().err().map(|e| redactor.apply(&e.to_string()).into_owned());
+    if let Err(e) = audit.append(actor, action, params, error) {
+        log::error!("Audit log: {}", e);
+    }
+    result
+}
+
+/// `record_as` for a command the user invoked from the app.
+pub fn record<T, E: std::fmt::Display>(
+    app: &tauri::AppHandle,
+    action: &str,
+    params: serde_json::Value,
+    result: Result<T, E>,
+) -> Result<T, E> {
+    record_as(app, USER, action, params, result)
+}
+
+/// Replace `value` with the redaction marker when `secret` is set.
+pub fn mask(value: &str, secret: bool) -> serde_json::Value {
+    serde_json::Value::String(if secret { REDACTED.to_string() } else { value.to_string() })
+}
+
+/// Short content fingerprint for params; a full 64-hex hash would be masked as a key.
+pub fn fingerprint(bytes: &[u8]) -> String {
+    crate::sha256_hex(bytes)[..16].to_string()
+}
+
+fn read_lines(audit: &AuditLog) -> Result<Vec<String>, String> {
+    match std::fs::read_to_string(&audit.path) {
+        Ok(raw) => Ok(raw.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", audit.path.display(), e)),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub entries: usize,
+    /// 1-based line of the first entry that breaks the chain.
+    pub broken_at_line: Option<usize>,
+    pub problem: Option<String>,
+    /// Hash of the last entry, to note down and compare against later.
+    pub head_hash: Option<String>,
+}
+
+/// Walk the chain from the first entry: sequence numbers, links and hashes.
+fn verify(audit: &AuditLog) -> Result<AuditVerification, String> {
+    let lines = read_lines(audit)?;
+    let mut prev_seq = 0;
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (i, line) in lines.iter().enumerate() {
+        let problem = match serde_json::from_str::<AuditEntry>(line) {
+            Err(e) => Some(format!("entry is not readable: {}", e)),
+            Ok(entry) if entry.seq != prev_seq + 1 => {
+                Some(format!("expected entry #{}, found #{}", prev_seq + 1, entry.seq))
+            }
+            Ok(entry) if entry.prev_hash != prev_hash => {
+                Some(format!("entry #{} does not link to the entry before it", entry.seq))
+            }
+            Ok(entry) if entry.compute_hash() != entry.hash => {
+                Some(format!("entry #{} was modified after it was written", entry.seq))
+            }
+            Ok(entry) => {
+                prev_seq = entry.seq;
+                prev_hash = entry.hash;
+                None
+            }
+        };
+        if let Some(problem) = problem {
+            return Ok(AuditVerification {
+                valid: false,
+                entries: lines.len(),
+                broken_at_line: Some(i + 1),
+                problem: Some(problem),
+                head_hash: None,
+            });
+        }
+    }
+    Ok(AuditVerification {
+        valid: true,
+        entries: lines.len(),
+        broken_at_line: None,
+        problem: None,
+        head_hash: (!lines.is_empty()).then_some(prev_hash),
+    })
+}
+
+#[tauri::command]
+pub fn verify_audit_log(audit: State<AuditLog>) -> Result<AuditVerification, String> {
+    verify(&audit)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditQuery {
+    pub actions: Option<Vec<String>>,
+    pub outcome: Option<Outcome>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+fn query(audit: &AuditLog, q: AuditQuery) -> Result<Vec<AuditEntry>, String> {
+    let limit = q.limit.unwrap_or(200).min(5000) as usize;
+    let in_range = |ts: &str| {
+        let Ok(t) = DateTime::parse_from_rfc3339(ts).map(|t| t.timestamp_millis()) else { return false };
+        q.from.map_or(true, |from| t >= from) && q.to.map_or(true, |to| t <= to)
+    };
+    Ok(read_lines(audit)?
+        .iter()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|e| q.actions.as_ref().map_or(true, |a| a.is_empty() || a.contains(&e.action)))
+        .filter(|e| q.outcome.map_or(true, |o| e.outcome == o))
+        .filter(|e| in_range(&e.ts))
+        .take(limit)
+        .collect())
+}
+
+/// Audit entries, newest first. `from`/`to` are epoch milliseconds. Unreadable lines
+/// are skipped here; `verify_audit_log` reports them.
+#[tauri::command]
+pub fn query_audit_log(audit: State<AuditLog>, query: Option<AuditQuery>) -> Result<Vec<AuditEntry>, String> {
+    self::query(&audit, query.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A log of three entries in a fresh directory, and its lines.
+    fn sample_log(name: &str) -> (AuditLog, Vec<String>) {
+        let dir = std::env::temp_dir().join(format!("hl-signalbot-audit-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let audit = AuditLog::new(&dir, "0.0.0".into());
+        for action in ["start_bot", "save_config", "stop_bot"] {
+            audit.append(USER, action, serde_json::json!({ "n": action }), None).unwrap();
+        }
+        let lines = read_lines(&audit).unwrap();
+        (audit, lines)
+    }
+
+    fn rewrite(audit: &AuditLog, lines: &[String]) {
+        std::fs::write(&audit.path, lines.iter().map(|l| format!("{}\n", l)).collect::<String>()).unwrap();
+    }
+
+    fn cleanup(audit: &AuditLog) {
+        let _ = std::fs::remove_dir_all(audit.path.parent().unwrap());
+    }
+
+    #[test]
+    fn intact_chain_verifies() {
+        let (audit, lines) = sample_log("intact");
+        let result = verify(&audit).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.entries, 3);
+        let last: AuditEntry = serde_json::from_str(&lines[2]).unwrap();
+        assert_eq!(result.head_hash, Some(last.hash));
+        cleanup(&audit);
+    }
+
+    #[test]
+    fn detects_an_edited_entry() {
+        let (audit, mut lines) = sample_log("edited");
+        lines[1] = lines[1].replace("save_config", "save_profile");
+        rewrite(&audit, &lines);
+        let result = verify(&audit).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.broken_at_line, Some(2));
+        assert_eq!(result.problem.as_deref(), Some("entry #2 was modified after it was written"));
+        cleanup(&audit);
+    }
+
+    #[test]
+    fn detects_reordered_entries() {
+        let (audit, mut lines) = sample_log("reordered");
+        lines.swap(1, 2);
+        rewrite(&audit, &lines);
+        let result = verify(&audit).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.broken_at_line, Some(2));
+        assert_eq!(result.problem.as_deref(), Some("expected entry #2, found #3"));
+        cleanup(&audit);
+    }
+
+    #[test]
+    fn detects_a_deleted_entry() {
+        let (audit, mut lines) = sample_log("deleted");
+        lines.remove(0);
+        rewrite(&audit, &lines);
+        let result = verify(&audit).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.broken_at_line, Some(1));
+        cleanup(&audit);
+    }
+
+    #[test]
+    fn detects_a_deleted_entry_with_renumbering() {
+        let (audit, lines) = sample_log("renumbered");
+        let mut third: AuditEntry = serde_json::from_str(&lines[2]).unwrap();
+        third.seq = 2;
+        third.hash = third.compute_hash();
+        rewrite(&audit, &[lines[0].clone(), serde_json::to_string(&third).unwrap()]);
+        let result = verify(&audit).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.problem.as_deref(), Some("entry #2 does not link to the entry before it"));
+        cleanup(&audit);
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::audit_log;
+use crate::config::{self, ConfigIssue, CONFIG_FILE};
+use crate::config_history::{self, ConfigChange, ConfigSource};
+use crate::dotenv::{EnvFile, ENV_FILE};
+use crate::fsutil::{self, WriteOptions};
+
+// --- Config Bundles ---
+//
+// A bundle is a shareable copy of the strategy part of config.json:
+//
+//   { format, bundleVersion, createdAt, appVersion, schemaVersion, name,
+//     checksum: "sha256:<hex>", config: {...} }
+//
+// The checksum covers the canonical JSON of `config` and catches hand edits or
+// truncated copies. Nothing tied to the sender's install travels: the wallet section,
+// key and token paths, and any value that appears in their .env are stripped on
+// export, and the local sections are never touched on import.
+
+const BUNDLE_FORMAT: &str = "hl-signalbot-config";
+const BUNDLE_VERSION: u32 = 1;
+const EXPORTS_DIR: &str = "exports";
+/// Sections that belong to the install, not the strategy.
+const LOCAL_SECTIONS: &[&str] = &["wallet", "telegram"];
+/// Keys dropped wherever they appear.
+const SECRET_KEYS: &[&str] = &["privateKey", "privateKeyPath", "tokenPath", "token"];
+/// .env values shorter than this (`true`, `BTC`) are too generic to treat as secrets.
+const MIN_ENV_SECRET_LEN: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundle {
+    pub format: String,
+    pub bundle_version: u32,
+    pub created_at: String,
+    pub app_version: String,
+    pub schema_version: u64,
+    pub name: Option<String>,
+    pub checksum: String,
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub path: String,
+    /// Dotted paths that were removed from the export.
+    pub stripped: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub name: Option<String>,
+    pub created_at: String,
+    pub app_version: String,
+    /// Top-level keys of the live config the import replaces.
+    pub sections: Vec<String>,
+    pub valid: bool,
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+    pub changes: Vec<ConfigChange>,
+    pub applied: bool,
+}
+
+fn checksum(config: &serde_json::Value) -> String {
+    format!("sha256:{}", crate::sha256_hex(crate::canonical_json(config).as_bytes()))
+}
+
+/// Values from .env that are long enough to be credentials or addresses.
+fn env_values(dir: &Path) -> Vec<String> {
+    EnvFile::load(&dir.join(ENV_FILE))
+        .unwrap_or_default()
+        .entries()
+        .map(|(_, v)| v.to_string())
+        .filter(|v| v.len() >= MIN_ENV_SECRET_LEN)
+        .collect()
+}
+
+fn strip_secrets(value: &mut serde_json::Value, path: &str, env: &[String], stripped: &mut Vec<String>) {
+    let Some(obj) = value.as_object_mut() else { return };
+    let keys: Vec<String> = obj.keys().cloned().collect();
+    for key in keys {
+        let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+        let secret = SECRET_KEYS.contains(&key.as_str())
+            || (path.is_empty() && key == "wallet")
+            || obj[&key].as_str().is_some_and(|s| env.iter().any(|e| s.contains(e.as_str())));
+        if secret {
+            obj.remove(&key);
+            stripped.push(child_path);
+        } else if let Some(child) = obj.get_mut(&key) {
+            strip_secrets(child, &child_path, env, stripped);
+        }
+    }
+}
+
+fn exports_dir(dir: &Path) -> PathBuf {
+    dir.join(EXPORTS_DIR)
+}
+
+/// File-name-safe version of a bundle name.
+fn slug(name: &str) -> String {
+    let s: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let s = s.trim_matches('-');
+    if s.is_empty() { "config".into() } else { s.chars().take(40).collect() }
+}
+
+fn parse_bundle(contents: &str) -> Result<ConfigBundle, String> {
+    let bundle: ConfigBundle = serde_json::from_str(contents).map_err(|e| format!("Not a config bundle: {}", e))?;
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(format!("Not a config bundle (format '{}')", bundle.format));
+    }
+    if bundle.bundle_version > BUNDLE_VERSION {
+        return Err("This bundle was made by a newer version of the app — update to import it".into());
+    }
+    if bundle.schema_version > config::SCHEMA_VERSION {
+        return Err(format!(
+            "This bundle uses config schema v{}, newer than this app's v{} — update to import it",
+            bundle.schema_version,
+            config::SCHEMA_VERSION
+        ));
+    }
+    if checksum(&bundle.config) != bundle.checksum {
+        return Err("Bundle checksum does not match its contents — it was edited or damaged".into());
+    }
+    if !bundle.config.is_object() {
+        return Err("Bundle config is not a JSON object".into());
+    }
+    Ok(bundle)
+}
+
+/// Write the strategy sections of config.json as a bundle in the exports folder of the
+/// data directory. The UI never picks the path, so it can't aim the write elsewhere.
+#[tauri::command]
+pub fn export_config_bundle(app: tauri::AppHandle, name: Option<String>) -> Result<ExportResult, String> {
+    let params = serde_json::json!({ "name": &name });
+    let result = export_bundle(&app, name);
+    audit_log::record(&app, "export_config_bundle", params, result)
+}
+
+fn export_bundle(app: &tauri::AppHandle, name: Option<String>) -> Result<ExportResult, String> {
+    let dir = crate::bot_config_dir(app)?;
+    let raw = std::fs::read_to_string(dir.join(CONFIG_FILE)).map_err(|e| format!("Failed to read {}: {}", CONFIG_FILE, e))?;
+    let mut cfg: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("config.json is not valid JSON: {}", e))?;
+    config::migrate(&mut cfg, &mut Vec::new())?;
+
+    let mut stripped = Vec::new();
+    strip_secrets(&mut cfg, "", &env_values(&dir), &mut stripped);
+    // Whatever is left of the local sections is install-specific; keep only the strategy
+    if let Some(obj) = cfg.as_object_mut() {
+        for key in LOCAL_SECTIONS {
+            if obj.remove(*key).is_some() {
+                stripped.push(key.to_string());
+            }
+        }
+    }
+
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let bundle = ConfigBundle {
+        format: BUNDLE_FORMAT.into(),
+        bundle_version: BUNDLE_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        app_version: crate::app_version(app),
+        schema_version: config::SCHEMA_VERSION,
+        name: name.clone(),
+        checksum: checksum(&cfg),
+        config: cfg,
+    };
+
+    let out = exports_dir(&dir);
+    std::fs::create_dir_all(&out).map_err(|e| format!("Failed to create {}: {}", out.display(), e))?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = out.join(format!("{}-{}.json", slug(name.as_deref().unwrap_or("config")), stamp));
+    let text = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    fsutil::write_file(&path, text, WriteOptions::PLAIN)?;
+    log::info!("Exported config bundle to {}", path.display());
+    Ok(ExportResult { path: path.to_string_lossy().to_string(), stripped })
+}
+
+/// Check a bundle and show what importing it would change. With `apply` set and a
+/// valid result, the bundle's sections replace those of the live config; the
+/// wallet and telegram sections are always kept as they are.
+#[tauri::command]
+pub fn import_config_bundle(app: tauri::AppHandle, contents: String, apply: Option<bool>) -> Result<ImportPreview, String> {
+    let apply = apply.unwrap_or(false);
+    let result = import_bundle(&app, &contents, apply);
+    if !apply {
+        // A preview changes nothing
+        return result;
+    }
+    let params = serde_json::json!({ "bytes": contents.len(), "fingerprint": audit_log::fingerprint(contents.as_bytes()) });
+    audit_log::record(&app, "import_config_bundle", params, result)
+}
+
+fn import_bundle(app: &tauri::AppHandle, contents: &str, apply: bool) -> Result<ImportPreview, String> {
+    let dir = crate::bot_config_dir(app)?;
+    let mut bundle = parse_bundle(contents)?;
+    config::migrate(&mut bundle.config, &mut Vec::new())?;
+
+    let current: serde_json::Value = std::fs::read_to_string(dir.join(CONFIG_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_else(|| serde_json::json!({}));
+    let mut merged = current.clone();
+    let target = merged.as_object_mut().ok_or("config.json is not a JSON object")?;
+    let mut sections = Vec::new();
+    for (key, value) in bundle.config.as_object().into_iter().flatten() {
+        if LOCAL_SECTIONS.contains(&key.as_str()) || SECRET_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        target.insert(key.clone(), value.clone());
+        if key != "schemaVersion" {
+            sections.push(key.clone());
+        }
+    }
+
+    let report = config::check(&merged);
+    let changes = config_history::diff(&current, &merged);
+    let mut applied = false;
+    if apply {
+        if !report.valid {
+            return Err(report.error_summary());
+        }
+        let text = serde_json::to_string_pretty(&merged).map_err(|e| e.to_string())?;
+        config_history::write_config_file(&dir, &text, ConfigSource::Import)?;
+        crate::config_watch::apply_reload_policy(app, &dir, &text);
+        log::info!("Imported config bundle {:?} ({} changes)", bundle.name, changes.len());
+        applied = true;
+    }
+
+    Ok(ImportPreview {
+        name: bundle.name,
+        created_at: bundle.created_at,
+        app_version: bundle.app_version,
+        sections,
+        valid: report.valid,
+        errors: report.errors,
+        warnings: report.warnings,
+        changes,
+        applied,
+    })
+}
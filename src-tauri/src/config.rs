@@ -0,0 +1,676 @@
+use serde::{Deserialize, Serialize};
+use crate::audit_log;
+use crate::config_history::{self, ConfigSource};
+
+// --- Bot Config Schema ---
+//
+// Mirrors bot/config.example.json. Every field is optional because the bot fills in
+// its own defaults; the typed structs are only used to catch wrong types and bad
+// values before they reach the bot. The JSON itself is written back untouched, so
+// keys this schema doesn't know about (e.g. `sdk`) survive a save.
+
+pub const CONFIG_FILE: &str = "config.json";
+
+/// Candle intervals Hyperliquid serves.
+const INTERVALS: &[&str] = &["1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "8h", "12h", "1d", "3d", "1w", "1M"];
+const TREND_MODES: &[&str] = &["both", "withTrendOnly", "disableCountertrendShorts"];
+const MAX_LEVERAGE: f64 = 50.0;
+/// Below this the bot would hit the info API harder than its rate limits allow.
+const MIN_POLL_MS: u64 = 1000;
+const WARN_POLL_MS: u64 = 5000;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BotConfig {
+    pub schema_version: Option<u64>,
+    pub mode: Option<String>,
+    pub wallet: WalletConfig,
+    pub market: MarketConfig,
+    pub signal: SignalConfig,
+    pub risk: RiskConfig,
+    pub exits: ExitsConfig,
+    pub execution: ExecutionConfig,
+    pub set_and_forget: SetAndForgetConfig,
+    pub display: DisplayConfig,
+    pub telegram: TelegramConfig,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WalletConfig {
+    pub address: Option<String>,
+    pub private_key_path: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarketConfig {
+    pub coin: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimeframeConfig {
+    pub trend: Option<String>,
+    pub trigger: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StochFilterConfig {
+    #[serde(deserialize_with = "flag")]
+    pub enabled: Option<bool>,
+    pub overbought: Option<f64>,
+    pub oversold: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SignalConfig {
+    pub poll_ms: Option<u64>,
+    pub timeframe: TimeframeConfig,
+    pub ema_trend_period: Option<u32>,
+    pub ema_trigger_period: Option<u32>,
+    pub atr_period: Option<u32>,
+    pub atr_mult: Option<f64>,
+    pub max_stop_pct: Option<f64>,
+    pub max_ema_dist_pct: Option<f64>,
+    pub stoch_filter: StochFilterConfig,
+    pub confirm_candles: Option<u32>,
+    pub trend_mode: Option<String>,
+    #[serde(deserialize_with = "flag")]
+    pub entry_on_candle_close: Option<bool>,
+    #[serde(deserialize_with = "flag")]
+    pub block_short_if_green_candle: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RiskConfig {
+    pub max_leverage: Option<f64>,
+    pub max_daily_loss_usd: Option<f64>,
+    pub cooldown_seconds: Option<f64>,
+    pub risk_per_trade_pct: Option<f64>,
+    pub margin_use_pct: Option<f64>,
+    pub min_hold_seconds: Option<f64>,
+    pub reentry_cooldown_seconds: Option<f64>,
+    pub loss_cooldown_minutes: Option<f64>,
+    pub atr_min_pct: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TakeProfit {
+    pub pct: Option<f64>,
+    pub close_frac: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrailingConfig {
+    #[serde(deserialize_with = "flag")]
+    pub enabled: Option<bool>,
+    pub kind: Option<String>,
+    pub trail_pct: Option<f64>,
+    pub min_update_seconds: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmaTrendBreakExit {
+    #[serde(deserialize_with = "flag")]
+    pub enabled: Option<bool>,
+    pub confirm_candles: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExitsConfig {
+    pub stop_loss_pct: Option<f64>,
+    pub max_margin_loss_pct: Option<f64>,
+    #[serde(deserialize_with = "flag")]
+    pub trail_to_breakeven_on_tp1: Option<bool>,
+    #[serde(deserialize_with = "flag")]
+    pub trail_stop_to_tp1_on_tp2: Option<bool>,
+    pub trailing_after_tp2: TrailingConfig,
+    pub ema_trend_break_exit: EmaTrendBreakExit,
+    pub tp: Vec<TakeProfit>,
+    pub runner_exit: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExecutionConfig {
+    pub order_type: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SetAndForgetConfig {
+    #[serde(deserialize_with = "flag")]
+    pub enabled: Option<bool>,
+    pub leverage: Option<f64>,
+    pub margin_use_pct: Option<f64>,
+    pub max_daily_loss_usd: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DisplayConfig {
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelegramConfig {
+    #[serde(deserialize_with = "flag")]
+    pub enabled: Option<bool>,
+    pub channel: Option<String>,
+    pub token_path: Option<String>,
+}
+
+/// A config flag. The bot reads flags with `String(x)`, so `"true"`/`"false"` and
+/// `"1"`/`"0"` work there as well as real booleans and must not fail validation.
+fn flag<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    struct FlagVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for FlagVisitor {
+        type Value = Option<bool>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("true or false")
+        }
+
+        fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Self::Value, E> {
+            Ok(Some(v))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            match v.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => Ok(Some(true)),
+                "false" | "0" => Ok(Some(false)),
+                _ => Err(E::invalid_value(serde::de::Unexpected::Str(v), &self)),
+            }
+        }
+
+        fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    deserializer.deserialize_option(FlagVisitor)
+}
+
+// --- Validation ---
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    /// Dotted path of the offending field, e.g. `exits.tp[1].pct`.
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+}
+
+impl ValidationReport {
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(ConfigIssue { field: field.into(), message: message.into() });
+    }
+
+    fn warn(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.warnings.push(ConfigIssue { field: field.into(), message: message.into() });
+    }
+
+    /// `Some(v)` outside `[min, max]` is an error; `None` is left to the bot's default.
+    fn range(&mut self, field: &str, value: Option<f64>, min: f64, max: f64) {
+        if let Some(v) = value {
+            if !v.is_finite() || v < min || v > max {
+                self.error(field, format!("must be between {} and {} (got {})", min, max, v));
+            }
+        }
+    }
+
+    /// Fractions (`0.03` = 3%) must be in `(0, max]`.
+    fn fraction(&mut self, field: &str, value: Option<f64>, max: f64) {
+        if let Some(v) = value {
+            if !v.is_finite() || v <= 0.0 || v > max {
+                self.error(field, format!("must be above 0 and at most {} (got {}) — fractions, e.g. 0.03 for 3%", max, v));
+            }
+        }
+    }
+
+    fn positive(&mut self, field: &str, value: Option<f64>) {
+        if let Some(v) = value {
+            if !v.is_finite() || v <= 0.0 {
+                self.error(field, format!("must be greater than 0 (got {})", v));
+            }
+        }
+    }
+
+    fn non_negative(&mut self, field: &str, value: Option<f64>) {
+        if let Some(v) = value {
+            if !v.is_finite() || v < 0.0 {
+                self.error(field, format!("must not be negative (got {})", v));
+            }
+        }
+    }
+
+    /// Short human summary of the errors, for commands that refuse invalid configs.
+    pub fn error_summary(&self) -> String {
+        let list: Vec<String> = self.errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect();
+        format!("Invalid config — {}", list.join("; "))
+    }
+}
+
+fn validate_signal(s: &SignalConfig, r: &mut ValidationReport) {
+    if let Some(ms) = s.poll_ms {
+        if ms < MIN_POLL_MS {
+            r.error("signal.pollMs", format!("must be at least {} ms", MIN_POLL_MS));
+        } else if ms < WARN_POLL_MS {
+            r.warn("signal.pollMs", format!("polling every {} ms risks Hyperliquid rate limits", ms));
+        }
+    }
+    for (field, tf) in [("signal.timeframe.trend", &s.timeframe.trend), ("signal.timeframe.trigger", &s.timeframe.trigger)] {
+        if let Some(tf) = tf {
+            if !INTERVALS.contains(&tf.as_str()) {
+                r.error(field, format!("'{}' is not a supported interval ({})", tf, INTERVALS.join(", ")));
+            }
+        }
+    }
+    for (field, period) in [
+        ("signal.emaTrendPeriod", s.ema_trend_period),
+        ("signal.emaTriggerPeriod", s.ema_trigger_period),
+        ("signal.atrPeriod", s.atr_period),
+    ] {
+        if period == Some(0) {
+            r.error(field, "must be at least 1");
+        }
+    }
+    r.positive("signal.atrMult", s.atr_mult);
+    r.fraction("signal.maxStopPct", s.max_stop_pct, 0.2);
+    r.range("signal.maxEmaDistPct", s.max_ema_dist_pct, 0.0, 0.1);
+
+    let stoch = &s.stoch_filter;
+    r.range("signal.stochFilter.overbought", stoch.overbought, 0.0, 100.0);
+    r.range("signal.stochFilter.oversold", stoch.oversold, 0.0, 100.0);
+    if let (Some(ob), Some(os)) = (stoch.overbought, stoch.oversold) {
+        if os >= ob {
+            r.error("signal.stochFilter.oversold", "must be below overbought");
+        }
+    }
+
+    match s.confirm_candles {
+        Some(0) => r.error("signal.confirmCandles", "must be at least 1"),
+        Some(n) if n > 3 => r.warn("signal.confirmCandles", format!("{} confirmation candles will make entries very late", n)),
+        _ => {}
+    }
+
+    if let Some(mode) = &s.trend_mode {
+        match TREND_MODES.iter().find(|m| m.eq_ignore_ascii_case(mode)) {
+            Some(m) if *m != mode => r.warn("signal.trendMode", format!("use '{}' — the app won't recognise '{}'", m, mode)),
+            Some(_) => {}
+            None => r.error("signal.trendMode", format!("'{}' is not one of {}", mode, TREND_MODES.join(", "))),
+        }
+    }
+}
+
+fn validate_risk(risk: &RiskConfig, r: &mut ValidationReport) {
+    r.range("risk.maxLeverage", risk.max_leverage, 1.0, MAX_LEVERAGE);
+    if risk.max_leverage.is_some_and(|l| l > 25.0 && l <= MAX_LEVERAGE) {
+        r.warn("risk.maxLeverage", "leverage above 25x liquidates on small moves");
+    }
+    r.positive("risk.maxDailyLossUsd", risk.max_daily_loss_usd);
+    r.fraction("risk.riskPerTradePct", risk.risk_per_trade_pct, 1.0);
+    if risk.risk_per_trade_pct.is_some_and(|p| p > 0.1 && p <= 1.0) {
+        r.warn("risk.riskPerTradePct", "risking more than 10% per trade");
+    }
+    r.fraction("risk.marginUsePct", risk.margin_use_pct, 1.0);
+    r.non_negative("risk.cooldownSeconds", risk.cooldown_seconds);
+    r.non_negative("risk.minHoldSeconds", risk.min_hold_seconds);
+    r.non_negative("risk.reentryCooldownSeconds", risk.reentry_cooldown_seconds);
+    r.non_negative("risk.lossCooldownMinutes", risk.loss_cooldown_minutes);
+    r.range("risk.atrMinPct", risk.atr_min_pct, 0.0, 0.1);
+}
+
+fn validate_exits(exits: &ExitsConfig, r: &mut ValidationReport) {
+    r.fraction("exits.stopLossPct", exits.stop_loss_pct, 0.5);
+    r.fraction("exits.maxMarginLossPct", exits.max_margin_loss_pct, 1.0);
+
+    let mut frac_sum = 0.0;
+    let mut prev_pct: Option<f64> = None;
+    for (i, tp) in exits.tp.iter().enumerate() {
+        let field = format!("exits.tp[{}]", i);
+        match tp.pct {
+            None => r.error(format!("{}.pct", field), "is required"),
+            Some(p) => {
+                r.fraction(&format!("{}.pct", field), Some(p), 1.0);
+                if prev_pct.is_some_and(|prev| p <= prev) {
+                    r.error(format!("{}.pct", field), "take-profit levels must increase (each TP further than the last)");
+                }
+                prev_pct = Some(p);
+            }
+        }
+        match tp.close_frac {
+            None => r.error(format!("{}.closeFrac", field), "is required"),
+            Some(f) => {
+                r.fraction(&format!("{}.closeFrac", field), Some(f), 1.0);
+                frac_sum += f;
+            }
+        }
+    }
+    if frac_sum > 1.0 + 1e-9 {
+        r.error("exits.tp", format!("closeFrac values add up to {:.2} — they can close at most the whole position (1.0)", frac_sum));
+    } else if (frac_sum - 1.0).abs() <= 1e-9 && exits.runner_exit.as_deref().is_some_and(|x| x.eq_ignore_ascii_case("signal")) {
+        r.warn("exits.runnerExit", "the take-profits close the whole position, so there is no runner left");
+    }
+
+    let trail = &exits.trailing_after_tp2;
+    if trail.enabled.unwrap_or(false) {
+        if let Some(kind) = trail.kind.as_deref() {
+            if !kind.eq_ignore_ascii_case("pct") {
+                r.error("exits.trailingAfterTp2.kind", format!("'{}' is not supported — use 'pct'", kind));
+            }
+        }
+        match trail.trail_pct {
+            None => r.error("exits.trailingAfterTp2.trailPct", "is required when trailing is enabled"),
+            p => r.fraction("exits.trailingAfterTp2.trailPct", p, 0.2),
+        }
+        r.non_negative("exits.trailingAfterTp2.minUpdateSeconds", trail.min_update_seconds);
+        if exits.tp.len() < 2 {
+            r.warn("exits.trailingAfterTp2", "trailing starts after TP2, but fewer than two take-profits are configured");
+        }
+    }
+
+    if exits.ema_trend_break_exit.confirm_candles == Some(0) {
+        r.error("exits.emaTrendBreakExit.confirmCandles", "must be at least 1");
+    }
+
+    if let Some(x) = exits.runner_exit.as_deref() {
+        if !x.is_empty() && !x.eq_ignore_ascii_case("signal") {
+            r.warn("exits.runnerExit", format!("'{}' is ignored by the bot — use 'signal' or leave it empty", x));
+        }
+    }
+}
+
+fn validate_set_and_forget(saf: &SetAndForgetConfig, r: &mut ValidationReport) {
+    r.range("setAndForget.leverage", saf.leverage, 1.0, MAX_LEVERAGE);
+    r.fraction("setAndForget.marginUsePct", saf.margin_use_pct, 1.0);
+    r.positive("setAndForget.maxDailyLossUsd", saf.max_daily_loss_usd);
+}
+
+fn validate_typed(cfg: &BotConfig, r: &mut ValidationReport) {
+    if cfg.schema_version.is_some_and(|v| v > SCHEMA_VERSION) {
+        r.warn("schemaVersion", "written by a newer version of the app — some settings may be ignored");
+    }
+    if let Some(mode) = cfg.mode.as_deref() {
+        if mode != "live" {
+            r.warn("mode", format!("'{}' is not a mode the bot knows — it always trades live", mode));
+        }
+    }
+
+    if let Some(addr) = cfg.wallet.address.as_deref() {
+        let hex = addr.strip_prefix("0x").unwrap_or("");
+        if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            r.error("wallet.address", "must be a 0x-prefixed 40 character hex address");
+        } else if hex.chars().all(|c| c == '0') {
+            r.warn("wallet.address", "is still the placeholder zero address");
+        }
+    }
+
+    match cfg.market.coin.as_deref().map(str::trim) {
+        Some("") => r.error("market.coin", "must not be empty"),
+        Some(c) if c.ends_with("-PERP") => r.error("market.coin", "use the bare coin name (e.g. BTC) — the bot appends -PERP"),
+        _ => {}
+    }
+
+    validate_signal(&cfg.signal, r);
+    validate_risk(&cfg.risk, r);
+    validate_exits(&cfg.exits, r);
+
+    if let Some(t) = cfg.execution.order_type.as_deref() {
+        if t != "taker" {
+            r.warn("execution.orderType", format!("'{}' is not supported — the bot always sends taker orders", t));
+        }
+    }
+
+    validate_set_and_forget(&cfg.set_and_forget, r);
+
+    if let Some(tz) = cfg.display.timezone.as_deref() {
+        if tz.parse::<chrono_tz::Tz>().is_err() {
+            r.error("display.timezone", format!("'{}' is not an IANA timezone (e.g. UTC, Europe/London)", tz));
+        }
+    }
+
+    if cfg.telegram.enabled.unwrap_or(false) {
+        let channel = cfg.telegram.channel.as_deref().unwrap_or("").trim();
+        if channel.is_empty() || channel == "@your_channel" {
+            r.warn("telegram.channel", "no channel set — the bot will need TG_CHAT in .env");
+        }
+        if cfg.telegram.token_path.as_deref().unwrap_or("").trim().is_empty() {
+            r.warn("telegram.tokenPath", "no token file set — the bot will need TG_TOKEN in .env");
+        }
+    }
+}
+
+/// Type-check `value` against the schema, then range- and consistency-check it.
+pub fn check(value: &serde_json::Value) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    if !value.is_object() {
+        report.error("", "config must be a JSON object");
+    } else {
+        match serde_path_to_error::deserialize::<_, BotConfig>(value) {
+            Ok(cfg) => validate_typed(&cfg, &mut report),
+            Err(e) => {
+                let field = e.path().to_string();
+                report.error(if field == "." { String::new() } else { field }, e.into_inner().to_string());
+            }
+        }
+    }
+    report.valid = report.errors.is_empty();
+    report
+}
+
+/// Parse and check config text, failing with the error summary if it is invalid.
+/// The result is migrated to the current schema and carries `schemaVersion`.
+pub fn parse_valid(contents: &str) -> Result<serde_json::Value, String> {
+    let mut value: serde_json::Value = serde_json::from_str(contents).map_err(|e| format!("config.json is not valid JSON: {}", e))?;
+    upgrade_checked(&mut value)?;
+    Ok(value)
+}
+
+/// Check `cfg`, then run any pending migrations so it is stamped with the current
+/// `schemaVersion`. Configs built by the UI leave the version to this step.
+pub fn upgrade_checked(cfg: &mut serde_json::Value) -> Result<ValidationReport, String> {
+    let report = check(cfg);
+    if !report.valid {
+        return Err(report.error_summary());
+    }
+    migrate(cfg, &mut Vec::new())?;
+    Ok(check(cfg))
+}
+
+#[tauri::command]
+pub fn validate_config(config: serde_json::Value) -> ValidationReport {
+    check(&config)
+}
+
+/// Validate and write config.json. Invalid configs are refused and the file is left as is.
+#[tauri::command]
+pub fn write_config(app: tauri::AppHandle, config: serde_json::Value) -> Result<ValidationReport, String> {
+    let params = serde_json::json!({ "fingerprint": audit_log::fingerprint(crate::canonical_json(&config).as_bytes()) });
+    let result = save_checked(&app, &config);
+    audit_log::record(&app, "write_config", params, result)
+}
+
+fn save_checked(app: &tauri::AppHandle, config: &serde_json::Value) -> Result<ValidationReport, String> {
+    let mut config = config.clone();
+    let report = upgrade_checked(&mut config)?;
+    let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    let dir = crate::bot_config_dir(app)?;
+    config_history::write_config_file(&dir, &text, ConfigSource::Ui)?;
+    crate::config_watch::apply_reload_policy(app, &dir, &text);
+    Ok(report)
+}
+
+// --- Migrations ---
+//
+// `schemaVersion` counts migration steps; a config without one is version 0.
+// `MIGRATIONS[n]` upgrades version n to n + 1. A step may rename keys or spell out
+// a default, but never change what the bot does: the defaults filled in are the
+// ones index.mjs falls back to when the key is missing, not the example values.
+
+pub const SCHEMA_VERSION: u64 = 2;
+
+type Migration = fn(&mut serde_json::Map<String, serde_json::Value>, &mut Vec<String>);
+
+const MIGRATIONS: &[Migration] = &[migrate_v0_normalise_trend_mode, migrate_v1_fill_defaults];
+
+#[derive(Debug)]
+pub struct MigrationReport {
+    pub from: u64,
+    pub to: u64,
+    pub changes: Vec<String>,
+    pub backup: Option<std::path::PathBuf>,
+}
+
+fn section<'a>(
+    cfg: &'a mut serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> &'a mut serde_json::Map<String, serde_json::Value> {
+    let entry = cfg.entry(name).or_insert_with(|| serde_json::json!({}));
+    if !entry.is_object() {
+        *entry = serde_json::json!({});
+    }
+    entry.as_object_mut().unwrap()
+}
+
+fn migrate_v0_normalise_trend_mode(cfg: &mut serde_json::Map<String, serde_json::Value>, changes: &mut Vec<String>) {
+    // The bot compares trendMode case-insensitively; the app only knows the camelCase form
+    if let Some(mode) = cfg.get_mut("signal").and_then(|s| s.get_mut("trendMode")) {
+        if let Some(m) = mode.as_str().and_then(|m| TREND_MODES.iter().find(|t| t.eq_ignore_ascii_case(m) && **t != m)) {
+            changes.push(format!("normalised signal.trendMode to '{}'", m));
+            *mode = serde_json::json!(m);
+        }
+    }
+}
+
+fn migrate_v1_fill_defaults(cfg: &mut serde_json::Map<String, serde_json::Value>, changes: &mut Vec<String>) {
+    use serde_json::json;
+    // index.mjs runs Set & Forget whenever the section exists and `enabled` isn't
+    // "false", so a section without the key is on, and a missing section is off.
+    let saf_on = cfg.get("setAndForget").is_some_and(|v| v.is_object());
+    let defaults = [
+        ("market", "coin", json!("BTC")),
+        ("signal", "pollMs", json!(20000)),
+        ("signal", "emaTrendPeriod", json!(50)),
+        ("signal", "emaTriggerPeriod", json!(20)),
+        ("signal", "trendMode", json!("both")),
+        ("signal", "entryOnCandleClose", json!(true)),
+        ("signal", "blockShortIfGreenCandle", json!(true)),
+        ("risk", "maxLeverage", json!(5)),
+        ("risk", "maxDailyLossUsd", json!(100)),
+        ("risk", "cooldownSeconds", json!(5)),
+        ("risk", "riskPerTradePct", json!(0.01)),
+        ("risk", "lossCooldownMinutes", json!(30)),
+        ("exits", "trailingAfterTp2", json!({ "enabled": false, "kind": "pct", "trailPct": 0.005, "minUpdateSeconds": 20 })),
+        ("exits", "emaTrendBreakExit", json!({ "enabled": false, "confirmCandles": 1 })),
+        ("execution", "orderType", json!("taker")),
+        ("setAndForget", "enabled", json!(saf_on)),
+        ("setAndForget", "leverage", json!(8)),
+        ("setAndForget", "marginUsePct", json!(0.75)),
+        ("setAndForget", "maxDailyLossUsd", json!(100)),
+        ("display", "timezone", json!("UTC")),
+    ];
+    for (name, key, value) in defaults {
+        let sec = section(cfg, name);
+        if sec.get(key).map_or(true, |v| v.is_null()) {
+            sec.insert(key.to_string(), value);
+            changes.push(format!("added {}.{}", name, key));
+        }
+    }
+}
+
+/// Run every pending migration on `cfg`. Returns the version it started from.
+pub fn migrate(cfg: &mut serde_json::Value, changes: &mut Vec<String>) -> Result<u64, String> {
+    let obj = cfg.as_object_mut().ok_or("config must be a JSON object")?;
+    let from = obj.get("schemaVersion").and_then(|v| v.as_u64()).unwrap_or(0);
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        step(obj, changes);
+        obj.insert("schemaVersion".into(), serde_json::json!(version as u64 + 1));
+    }
+    Ok(from)
+}
+
+/// Upgrade config.json in `dir` to the current schema, keeping the original in the
+/// config history. Returns `None` when there is no config or it is already current.
+pub fn migrate_config_file(dir: &std::path::Path) -> Result<Option<MigrationReport>, String> {
+    let path = dir.join(CONFIG_FILE);
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(r) => r,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let mut cfg: serde_json::Value =
+        serde_json::from_str(&raw).map_err(|e| format!("config.json is not valid JSON, not migrating: {}", e))?;
+
+    let mut changes = Vec::new();
+    let from = migrate(&mut cfg, &mut changes)?;
+    if from >= SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    // The pre-migration file is kept in the config history
+    let text = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+    let backup = config_history::write_config_file(dir, &text, ConfigSource::Migration)?;
+
+    let report = check(&cfg);
+    for issue in &report.errors {
+        log::warn!("Migrated config.json has an invalid field {}: {}", issue.field, issue.message);
+    }
+    Ok(Some(MigrationReport { from, to: SCHEMA_VERSION, changes, backup }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_flags_written_as_strings() {
+        let cfg = serde_json::json!({
+            "telegram": { "enabled": "false" },
+            "setAndForget": { "enabled": "TRUE" },
+            "signal": { "entryOnCandleClose": "1", "stochFilter": { "enabled": "0" } },
+            "exits": { "trailToBreakevenOnTp1": true, "trailingAfterTp2": { "enabled": null } },
+        });
+        let report = check(&cfg);
+        assert!(report.valid, "{:?}", report.errors);
+        let typed: BotConfig = serde_json::from_value(cfg).unwrap();
+        assert_eq!(typed.telegram.enabled, Some(false));
+        assert_eq!(typed.set_and_forget.enabled, Some(true));
+        assert_eq!(typed.signal.entry_on_candle_close, Some(true));
+        assert_eq!(typed.signal.stoch_filter.enabled, Some(false));
+        assert_eq!(typed.exits.trailing_after_tp2.enabled, None);
+    }
+
+    #[test]
+    fn rejects_other_flag_values() {
+        for bad in [serde_json::json!("yes please"), serde_json::json!(2), serde_json::json!([])] {
+            let report = check(&serde_json::json!({ "telegram": { "enabled": bad } }));
+            assert!(!report.valid);
+            assert_eq!(report.errors[0].field, "telegram.enabled");
+        }
+    }
+}
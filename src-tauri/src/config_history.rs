@@ -0,0 +1,290 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::config::{self, CONFIG_FILE};
+use crate::fsutil::{self, WriteOptions};
+
+// --- Config History ---
+//
+// Before config.json is replaced, the version being replaced is copied verbatim to
+// config-history/<ts_ms>-<source>.json. The file name is the version id, so listing
+// needs no index file and a half-written snapshot never corrupts anything else.
+//
+// `<source>` says where the snapshotted content came from, not what replaced it.
+// Every write records its source and content hash in config-history/head.json; when
+// config.json no longer matches that hash it was edited outside the app.
+
+const HISTORY_DIR: &str = "config-history";
+const HEAD_FILE: &str = "head.json";
+const MAX_VERSIONS: usize = 200;
+/// Version id accepted by the diff command for the live config.json.
+const CURRENT: &str = "current";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    Ui,
+    Migration,
+    Import,
+    Restore,
+    Profile,
+    /// The key store setup dropping the plaintext key path.
+    Keystore,
+    /// Edited outside the app, or written before sources were tracked.
+    External,
+}
+
+impl ConfigSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigSource::Ui => "ui",
+            ConfigSource::Migration => "migration",
+            ConfigSource::Import => "import",
+            ConfigSource::Restore => "restore",
+            ConfigSource::Profile => "profile",
+            ConfigSource::Keystore => "keystore",
+            ConfigSource::External => "external",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ui" => Some(ConfigSource::Ui),
+            "migration" => Some(ConfigSource::Migration),
+            "import" => Some(ConfigSource::Import),
+            "restore" => Some(ConfigSource::Restore),
+            "profile" => Some(ConfigSource::Profile),
+            "keystore" => Some(ConfigSource::Keystore),
+            "external" => Some(ConfigSource::External),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigVersion {
+    pub id: String,
+    /// When the version was replaced (epoch ms).
+    pub ts: i64,
+    /// Where this version's content came from.
+    pub source: ConfigSource,
+    pub size: u64,
+}
+
+fn history_dir(dir: &Path) -> PathBuf {
+    dir.join(HISTORY_DIR)
+}
+
+/// Source and hash of the last config.json the app wrote.
+#[derive(Debug, Deserialize, Serialize)]
+struct Head {
+    source: ConfigSource,
+    hash: String,
+}
+
+fn read_head(dir: &Path) -> Option<Head> {
+    let raw = std::fs::read_to_string(history_dir(dir).join(HEAD_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Whether `contents` is exactly what the app last wrote to config.json, as opposed
+/// to an edit from outside.
+pub fn written_by_app(dir: &Path, contents: &[u8]) -> bool {
+    read_head(dir).is_some_and(|h| h.hash == crate::sha256_hex(contents))
+}
+
+/// Where the content of config.json came from.
+fn current_source(dir: &Path, current: &str) -> ConfigSource {
+    match read_head(dir) {
+        Some(head) if head.hash == crate::sha256_hex(current.as_bytes()) => head.source,
+        _ => ConfigSource::External,
+    }
+}
+
+fn parse_id(id: &str) -> Option<(i64, ConfigSource)> {
+    let (ts, source) = id.split_once('-')?;
+    Some((ts.parse().ok()?, ConfigSource::parse(source)?))
+}
+
+/// Path of a stored version. Ids come from the UI, so anything that doesn't look
+/// like one we generated is rejected before it touches the filesystem.
+fn version_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    parse_id(id).ok_or_else(|| format!("Unknown config version '{}'", id))?;
+    let path = history_dir(dir).join(format!("{}.json", id));
+    if !path.is_file() {
+        return Err(format!("Config version '{}' no longer exists", id));
+    }
+    Ok(path)
+}
+
+fn list_versions(dir: &Path) -> Vec<ConfigVersion> {
+    let mut versions: Vec<ConfigVersion> = std::fs::read_dir(history_dir(dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".json")?;
+            let (ts, source) = parse_id(id)?;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            Some(ConfigVersion { id: id.to_string(), ts, source, size })
+        })
+        .collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.ts));
+    versions
+}
+
+fn prune(dir: &Path) {
+    for old in list_versions(dir).into_iter().skip(MAX_VERSIONS) {
+        let _ = std::fs::remove_file(history_dir(dir).join(format!("{}.json", old.id)));
+    }
+}
+
+/// Copy the current config.json into the history, labelled with where it came from,
+/// unless it is missing or identical to `next`. Returns the snapshot path.
+pub fn snapshot(dir: &Path, next: &str) -> Result<Option<PathBuf>, String> {
+    let current = match std::fs::read_to_string(dir.join(CONFIG_FILE)) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", CONFIG_FILE, e)),
+    };
+    if current == next {
+        return Ok(None);
+    }
+
+    let source = current_source(dir, &current);
+    let hist = history_dir(dir);
+    std::fs::create_dir_all(&hist).map_err(|e| format!("Failed to create {}: {}", hist.display(), e))?;
+    let mut ts = crate::db::now_ms();
+    let path = loop {
+        let p = hist.join(format!("{}-{}.json", ts, source.as_str()));
+        if !p.exists() {
+            break p;
+        }
+        ts += 1;
+    };
+    fsutil::write_file(&path, current, WriteOptions::PLAIN)?;
+    prune(dir);
+    Ok(Some(path))
+}
+
+/// Atomically replace config.json with `contents` from `source`, snapshotting the
+/// version it replaces. The head is recorded first so the config watcher never sees
+/// the new file without it.
+pub fn write_config_file(dir: &Path, contents: &str, source: ConfigSource) -> Result<Option<PathBuf>, String> {
+    let snap = snapshot(dir, contents)?;
+    let hist = history_dir(dir);
+    std::fs::create_dir_all(&hist).map_err(|e| format!("Failed to create {}: {}", hist.display(), e))?;
+    let head = Head { source, hash: crate::sha256_hex(contents.as_bytes()) };
+    let head = serde_json::to_string(&head).map_err(|e| e.to_string())?;
+    fsutil::write_file(&hist.join(HEAD_FILE), &head, WriteOptions::PLAIN)?;
+    fsutil::write_file(&dir.join(CONFIG_FILE), contents, WriteOptions::BACKUP)?;
+    Ok(snap)
+}
+
+fn load_version(dir: &Path, id: &str) -> Result<serde_json::Value, String> {
+    let path = if id == CURRENT { dir.join(CONFIG_FILE) } else { version_path(dir, id)? };
+    let raw = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Config version '{}' is not valid JSON: {}", id, e))
+}
+
+// --- Diff ---
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    /// Dotted key path, e.g. `risk.maxLeverage` or `exits.tp[1].pct`.
+    pub path: String,
+    pub kind: ChangeKind,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) }
+}
+
+fn diff_values(path: &str, before: Option<&serde_json::Value>, after: Option<&serde_json::Value>, out: &mut Vec<ConfigChange>) {
+    use serde_json::Value;
+    match (before, after) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            for (k, va) in a {
+                diff_values(&join_key(path, k), Some(va), b.get(k), out);
+            }
+            for (k, vb) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                diff_values(&join_key(path, k), None, Some(vb), out);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_values(&format!("{}[{}]", path, i), a.get(i), b.get(i), out);
+            }
+        }
+        (Some(a), Some(b)) if a == b => {}
+        (a, b) => out.push(ConfigChange {
+            path: path.to_string(),
+            kind: match (a, b) {
+                (None, _) => ChangeKind::Added,
+                (_, None) => ChangeKind::Removed,
+                _ => ChangeKind::Changed,
+            },
+            before: a.cloned(),
+            after: b.cloned(),
+        }),
+    }
+}
+
+pub fn diff(before: &serde_json::Value, after: &serde_json::Value) -> Vec<ConfigChange> {
+    let mut out = Vec::new();
+    diff_values("", Some(before), Some(after), &mut out);
+    out
+}
+
+// --- Commands ---
+
+/// Stored config versions, newest first.
+#[tauri::command]
+pub fn list_config_versions(app: tauri::AppHandle) -> Result<Vec<ConfigVersion>, String> {
+    Ok(list_versions(&crate::bot_config_dir(&app)?))
+}
+
+/// Key-level changes going from version `from` to version `to` (default: the live
+/// config). Either id may be "current".
+#[tauri::command]
+pub fn diff_config_versions(app: tauri::AppHandle, from: String, to: Option<String>) -> Result<Vec<ConfigChange>, String> {
+    let dir = crate::bot_config_dir(&app)?;
+    let before = load_version(&dir, &from)?;
+    let after = load_version(&dir, to.as_deref().unwrap_or(CURRENT))?;
+    Ok(diff(&before, &after))
+}
+
+/// Make a stored version the live config.json again. Old versions are migrated to
+/// the current schema first and refused if they no longer validate. The config
+/// being replaced is itself kept in the history.
+#[tauri::command]
+pub fn restore_config_version(app: tauri::AppHandle, id: String) -> Result<serde_json::Value, String> {
+    let result = restore_version(&app, &id);
+    crate::audit_log::record(&app, "restore_config_version", serde_json::json!({ "id": id }), result)
+}
+
+fn restore_version(app: &tauri::AppHandle, id: &str) -> Result<serde_json::Value, String> {
+    let dir = crate::bot_config_dir(app)?;
+    let mut cfg = load_version(&dir, id)?;
+    config::migrate(&mut cfg, &mut Vec::new())?;
+    let report = config::check(&cfg);
+    if !report.valid {
+        return Err(report.error_summary());
+    }
+    let text = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+    write_config_file(&dir, &text, ConfigSource::Restore)?;
+    crate::config_watch::apply_reload_policy(app, &dir, &text);
+    log::info!("Restored config.json from version {}", id);
+    Ok(cfg)
+}
@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use crate::audit_log;
+use crate::config::{self, ConfigIssue, CONFIG_FILE};
+use crate::config_history;
+use crate::settings::{self, ReloadPolicy};
+use crate::RestartMode;
+
+// --- Config Hot Reload ---
+//
+// The bot only reads config.json at start, so an edit does nothing until it is
+// restarted. The watcher notices edits made outside the app, checks them against the
+// schema and applies the reload policy from app settings. An edit that fails
+// validation is reported and otherwise ignored: the running bot keeps its old config.
+//
+// The app's own writes are recognised by the hash config_history records and skipped.
+// Saves that leave the restart to the policy hand it over via `apply_reload_policy`;
+// profile activation brings its own restart choice.
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReloadAction {
+    /// The bot is stopped; the change applies on its next start.
+    NextStart,
+    Ignored,
+    /// Waiting for the user to choose in the UI.
+    Prompt,
+    Restarted,
+    RestartWhenFlat,
+    /// The new config is invalid and was not applied.
+    Rejected,
+    RestartFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangeEvent {
+    pub valid: bool,
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+    pub policy: ReloadPolicy,
+    pub action: ReloadAction,
+    pub error: Option<String>,
+}
+
+fn bot_running(app: &tauri::AppHandle) -> bool {
+    *app.state::<crate::BotState>().running.lock().unwrap()
+}
+
+fn handle_change(app: &tauri::AppHandle, dir: &Path, raw: &[u8]) -> ConfigChangeEvent {
+    let policy = settings::load(dir).config_reload;
+    let report = match serde_json::from_slice::<serde_json::Value>(raw) {
+        Ok(v) => config::check(&v),
+        Err(e) => {
+            let mut r = config::ValidationReport::default();
+            r.errors.push(ConfigIssue { field: String::new(), message: format!("not valid JSON: {}", e) });
+            r
+        }
+    };
+    let mut event = ConfigChangeEvent {
+        valid: report.valid,
+        errors: report.errors,
+        warnings: report.warnings,
+        policy,
+        action: ReloadAction::NextStart,
+        error: None,
+    };
+
+    if !event.valid {
+        log::warn!("config.json changed but is invalid — keeping the running config");
+        event.action = ReloadAction::Rejected;
+        return event;
+    }
+    if !bot_running(app) {
+        return event;
+    }
+
+    let mode = match policy {
+        ReloadPolicy::Ignore => {
+            event.action = ReloadAction::Ignored;
+            return event;
+        }
+        ReloadPolicy::Prompt => {
+            event.action = ReloadAction::Prompt;
+            return event;
+        }
+        ReloadPolicy::Restart => RestartMode::Now,
+        ReloadPolicy::RestartWhenFlat => RestartMode::WhenFlat,
+    };
+    log::info!("config.json changed — applying reload policy {:?}", policy);
+    let result = tauri::async_runtime::block_on(crate::restart_for_config_change(app, mode));
+    let params = serde_json::json!({ "mode": mode, "policy": policy });
+    match audit_log::record_as(app, "config-watch", "apply_config_change", params, result) {
+        Ok(_) if mode == RestartMode::Now => event.action = ReloadAction::Restarted,
+        Ok(_) => event.action = ReloadAction::RestartWhenFlat,
+        Err(e) => {
+            log::error!("Config reload restart failed: {}", e);
+            event.action = ReloadAction::RestartFailed;
+            event.error = Some(e);
+        }
+    }
+    event
+}
+
+/// Apply the reload policy to config.json the app has just written, off the calling
+/// thread, and emit `config-changed` as the watcher would.
+pub fn apply_reload_policy(app: &tauri::AppHandle, dir: &Path, contents: &str) {
+    let (app, dir, raw) = (app.clone(), dir.to_path_buf(), contents.as_bytes().to_vec());
+    std::thread::spawn(move || {
+        let event = handle_change(&app, &dir, &raw);
+        let _ = app.emit("config-changed", &event);
+    });
+}
+
+/// Poll config.json once a second and emit `config-changed` after each settled edit
+/// from outside the app. A change must read the same on two consecutive polls, so
+/// editors that write in several steps are seen once.
+pub fn spawn_config_watcher(app: tauri::AppHandle, config_dir: PathBuf) {
+    std::thread::spawn(move || {
+        let path = config_dir.join(CONFIG_FILE);
+        let read_hash = || std::fs::read(&path).ok().map(|b| (crate::sha256_hex(&b), b));
+        let mut applied = read_hash().map(|(h, _)| h);
+        let mut pending: Option<String> = None;
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            let Some((hash, raw)) = read_hash() else { continue };
+            if applied.as_deref() == Some(hash.as_str()) {
+                pending = None;
+                continue;
+            }
+            if pending.as_deref() != Some(hash.as_str()) {
+                pending = Some(hash);
+                continue;
+            }
+
+            applied = pending.take();
+            if config_history::written_by_app(&config_dir, &raw) {
+                continue;
+            }
+            let event = handle_change(&app, &config_dir, &raw);
+            let _ = app.emit("config-changed", &event);
+        }
+    });
+}
+
+/// Answer to a `prompt` reload: apply the current config.json to the running bot.
+#[tauri::command]
+pub async fn apply_config_change(app: tauri::AppHandle, mode: RestartMode) -> Result<bool, String> {
+    let result = apply(&app, mode).await;
+    audit_log::record(&app, "apply_config_change", serde_json::json!({ "mode": mode }), result)
+}
+
+async fn apply(app: &tauri::AppHandle, mode: RestartMode) -> Result<bool, String> {
+    let cfg_path = crate::bot_config_dir(app)?.join(CONFIG_FILE);
+    let raw = std::fs::read_to_string(&cfg_path).map_err(|e| format!("Failed to read {}: {}", cfg_path.display(), e))?;
+    config::parse_valid(&raw)?;
+    crate::restart_for_config_change(app, mode).await
+}
@@ -0,0 +1,654 @@
+use std::path::Path;
+use std::sync::Mutex;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::trades::{TailPosition, TradeRecord};
+
+// --- Local Store ---
+//
+// Embedded SQLite database with queryable copies of trades.jsonl, bot events and
+// supervisor runs. trades.jsonl stays the bot's source of truth: rows here are
+// ingested incrementally by byte offset, and the raw record is UNIQUE so re-reading
+// a rotated or truncated log never duplicates a trade.
+
+const SCHEMA: &[&str] = &[
+    // v1
+    "CREATE TABLE runs (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        started_ms  INTEGER NOT NULL,
+        stopped_ms  INTEGER,
+        exit_code   INTEGER
+    );
+    CREATE TABLE trades (
+        id           INTEGER PRIMARY KEY AUTOINCREMENT,
+        ts           TEXT NOT NULL,
+        ts_ms        INTEGER,
+        action       TEXT NOT NULL,
+        side         TEXT,
+        size_btc     REAL,
+        entry_px     REAL,
+        exit_px      REAL,
+        notional_usd REAL,
+        pnl_usd      REAL,
+        hl_pnl       REAL,
+        hl_fee       REAL,
+        leader       TEXT,
+        partial      INTEGER,
+        tp_index     INTEGER,
+        exit_reason  TEXT,
+        run_id       INTEGER REFERENCES runs(id),
+        raw          TEXT NOT NULL UNIQUE
+    );
+    CREATE INDEX trades_ts ON trades(ts_ms);
+    CREATE TABLE events (
+        id       INTEGER PRIMARY KEY AUTOINCREMENT,
+        run_id   INTEGER REFERENCES runs(id),
+        ts_ms    INTEGER NOT NULL,
+        type     TEXT NOT NULL,
+        payload  TEXT NOT NULL
+    );
+    CREATE INDEX events_run ON events(run_id, ts_ms);
+    CREATE TABLE ingest_state (
+        source    TEXT PRIMARY KEY,
+        identity  TEXT,
+        offset    INTEGER NOT NULL
+    );",
+    // v2: run fingerprints and trade attribution
+    "ALTER TABLE runs ADD COLUMN exit_kind TEXT;
+    ALTER TABLE runs ADD COLUMN app_version TEXT;
+    ALTER TABLE runs ADD COLUMN bot_version TEXT;
+    ALTER TABLE runs ADD COLUMN node_version TEXT;
+    ALTER TABLE runs ADD COLUMN config_hash TEXT;
+    ALTER TABLE runs ADD COLUMN config_snapshot TEXT;
+    CREATE INDEX runs_config ON runs(config_hash);
+    CREATE INDEX trades_run ON trades(run_id);",
+    // v3: fingerprint of the bytes before the ingest offset
+    "ALTER TABLE ingest_state ADD COLUMN fingerprint TEXT;",
+];
+
+/// Per-poll snapshots are already covered by the equity store and would dwarf
+/// everything else in the events table.
+const SKIPPED_EVENT_TYPES: &[&str] = &["position", "equity", "pnl"];
+
+pub struct Db {
+    conn: Mutex<Connection>,
+}
+
+/// How a supervised bot process ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitKind {
+    /// Exited on its own with code 0.
+    Clean,
+    /// Stopped by the user (stop, restart or app quit).
+    Stopped,
+    /// Exited on its own with a non-zero code.
+    Crashed,
+    /// Terminated by a signal the supervisor did not send.
+    Killed,
+    /// The supervisor lost track of the process.
+    Lost,
+    /// Still open when the app last exited without closing it.
+    Abandoned,
+}
+
+impl ExitKind {
+    pub fn from_code(code: Option<i32>) -> Self {
+        match code {
+            Some(0) => ExitKind::Clean,
+            Some(_) => ExitKind::Crashed,
+            None => ExitKind::Killed,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExitKind::Clean => "clean",
+            ExitKind::Stopped => "stopped",
+            ExitKind::Crashed => "crashed",
+            ExitKind::Killed => "killed",
+            ExitKind::Lost => "lost",
+            ExitKind::Abandoned => "abandoned",
+        }
+    }
+}
+
+/// What the bot was launched with, recorded at the start of each run.
+#[derive(Debug, Default)]
+pub struct RunInfo {
+    pub app_version: String,
+    pub bot_version: Option<String>,
+    pub node_version: Option<String>,
+    pub config_hash: Option<String>,
+    pub config_snapshot: Option<String>,
+}
+
+/// Attribute unassigned trades to the run whose time window contains them.
+const ATTRIBUTE_TRADES_SQL: &str = "UPDATE trades SET run_id = (
+        SELECT r.id FROM runs r
+        WHERE trades.ts_ms >= r.started_ms AND (r.stopped_ms IS NULL OR trades.ts_ms <= r.stopped_ms)
+        ORDER BY r.started_ms DESC LIMIT 1)
+    WHERE run_id IS NULL AND ts_ms IS NOT NULL";
+
+pub fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    for (i, sql) in SCHEMA.iter().enumerate().skip(version) {
+        conn.execute_batch(&format!("BEGIN; {} PRAGMA user_version = {}; COMMIT;", sql, i + 1))?;
+    }
+    Ok(())
+}
+
+impl Db {
+    /// Open (or create) `signalbot.db` in the data dir. Falls back to an in-memory
+    /// database so a corrupt file degrades history features instead of the app.
+    pub fn open(data_dir: &Path) -> Self {
+        let path = data_dir.join("signalbot.db");
+        let conn = Connection::open(&path)
+            .and_then(|c| {
+                c.pragma_update(None, "journal_mode", "WAL")?;
+                c.busy_timeout(std::time::Duration::from_secs(5))?;
+                migrate(&c)?;
+                // Nothing is running yet, so any open run outlived a crashed or killed app.
+                // Close it at its last recorded event so it stops claiming new trades.
+                c.execute(
+                    "UPDATE runs SET exit_kind = ?1, stopped_ms = COALESCE(
+                        (SELECT MAX(ts_ms) FROM events WHERE run_id = runs.id), started_ms)
+                     WHERE stopped_ms IS NULL",
+                    [ExitKind::Abandoned.as_str()],
+                )?;
+                Ok(c)
+            })
+            .unwrap_or_else(|e| {
+                log::error!("Cannot open {}: {} — using in-memory store", path.display(), e);
+                let c = Connection::open_in_memory().expect("in-memory sqlite");
+                let _ = migrate(&c);
+                c
+            });
+        Self { conn: Mutex::new(conn) }
+    }
+
+    pub fn with<T>(&self, f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let mut conn = self.conn.lock().unwrap();
+        f(&mut conn).map_err(|e| format!("Database error: {}", e))
+    }
+
+    pub fn ingest_position(&self, source: &str) -> TailPosition {
+        self.with(|c| {
+            c.query_row(
+                "SELECT identity, offset, fingerprint FROM ingest_state WHERE source = ?1",
+                [source],
+                |r| {
+                    Ok(TailPosition {
+                        identity: r.get(0)?,
+                        offset: r.get::<_, i64>(1)? as u64,
+                        fingerprint: r.get(2)?,
+                    })
+                },
+            )
+            .optional()
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+    }
+
+    /// Insert trade records and advance the stored file position in one transaction,
+    /// so a crash between the two can neither skip nor double-count a record.
+    pub fn ingest_trades(&self, records: &[TradeRecord], position: &TailPosition) -> Result<usize, String> {
+        self.with(|c| {
+            let tx = c.transaction()?;
+            let mut inserted = 0;
+            {
+                let mut stmt = tx.prepare_cached(
+                    "INSERT OR IGNORE INTO trades (ts, ts_ms, action, side, size_btc, entry_px, exit_px,
+                        notional_usd, pnl_usd, hl_pnl, hl_fee, leader, partial, tp_index, exit_reason, raw)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+                )?;
+                for rec in records {
+                    let raw = serde_json::to_string(rec).unwrap_or_default();
+                    inserted += stmt.execute(params![
+                        rec.ts,
+                        rec.ts_ms(),
+                        rec.action.as_str(),
+                        rec.side.map(|s| s.as_str()),
+                        rec.size_btc,
+                        rec.entry_px,
+                        rec.exit_px,
+                        rec.notional_usd,
+                        rec.pnl_usd,
+                        rec.hl_pnl,
+                        rec.hl_fee,
+                        rec.leader,
+                        rec.partial,
+                        rec.tp_index,
+                        rec.exit_reason(),
+                        raw,
+                    ])?;
+                }
+            }
+            tx.execute(ATTRIBUTE_TRADES_SQL, [])?;
+            tx.execute(
+                "INSERT INTO ingest_state (source, identity, offset, fingerprint) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(source) DO UPDATE SET identity = excluded.identity, offset = excluded.offset,
+                    fingerprint = excluded.fingerprint",
+                params![crate::trades::TRADES_SOURCE, position.identity, position.offset as i64, position.fingerprint],
+            )?;
+            tx.commit()?;
+            Ok(inserted)
+        })
+    }
+
+    pub fn begin_run(&self, info: &RunInfo) -> Result<i64, String> {
+        self.with(|c| {
+            c.execute(
+                "INSERT INTO runs (started_ms, app_version, bot_version, node_version, config_hash, config_snapshot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    now_ms(),
+                    info.app_version,
+                    info.bot_version,
+                    info.node_version,
+                    info.config_hash,
+                    info.config_snapshot,
+                ],
+            )?;
+            Ok(c.last_insert_rowid())
+        })
+    }
+
+    pub fn end_run(&self, run_id: i64, exit_code: Option<i32>, kind: ExitKind) -> Result<(), String> {
+        self.with(|c| {
+            c.execute(
+                "UPDATE runs SET stopped_ms = ?2, exit_code = ?3, exit_kind = ?4 WHERE id = ?1 AND stopped_ms IS NULL",
+                params![run_id, now_ms(), exit_code, kind.as_str()],
+            )?;
+            c.execute(ATTRIBUTE_TRADES_SQL, [])?;
+            Ok(())
+        })
+    }
+
+    pub fn insert_event(&self, run_id: Option<i64>, event: &serde_json::Value) {
+        let Some(kind) = event["type"].as_str() else { return };
+        if SKIPPED_EVENT_TYPES.contains(&kind) {
+            return;
+        }
+        let res = self.with(|c| {
+            c.execute(
+                "INSERT INTO events (run_id, ts_ms, type, payload) VALUES (?1, ?2, ?3, ?4)",
+                params![run_id, now_ms(), kind, event.to_string()],
+            )
+        });
+        if let Err(e) = res {
+            log::warn!("Failed to store bot event: {}", e);
+        }
+    }
+}
+
+// --- Query Commands ---
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TradeQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub action: Option<String>,
+    pub exit_reason: Option<String>,
+    pub run_id: Option<i64>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeRow {
+    pub id: i64,
+    pub ts: String,
+    pub ts_ms: Option<i64>,
+    pub action: String,
+    pub side: Option<String>,
+    pub size_btc: Option<f64>,
+    pub entry_px: Option<f64>,
+    pub exit_px: Option<f64>,
+    pub notional_usd: Option<f64>,
+    pub pnl_usd: Option<f64>,
+    pub hl_pnl: Option<f64>,
+    pub hl_fee: Option<f64>,
+    pub leader: Option<String>,
+    pub partial: Option<bool>,
+    pub tp_index: Option<u32>,
+    pub exit_reason: Option<String>,
+    pub run_id: Option<i64>,
+}
+
+/// Appends `ts_ms` range conditions shared by the aggregate queries.
+fn time_filter(from: Option<i64>, to: Option<i64>, clauses: &mut Vec<String>, args: &mut Vec<rusqlite::types::Value>) {
+    if let Some(f) = from {
+        args.push(f.into());
+        clauses.push(format!("ts_ms >= ?{}", args.len()));
+    }
+    if let Some(t) = to {
+        args.push(t.into());
+        clauses.push(format!("ts_ms < ?{}", args.len()));
+    }
+}
+
+fn where_sql(clauses: &[String]) -> String {
+    if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    }
+}
+
+/// Newest-first trade history with optional filters.
+#[tauri::command]
+pub fn query_trades(db: State<Db>, query: Option<TradeQuery>) -> Result<Vec<TradeRow>, String> {
+    let q = query.unwrap_or_default();
+    let mut clauses = Vec::new();
+    let mut args: Vec<rusqlite::types::Value> = Vec::new();
+    time_filter(q.from, q.to, &mut clauses, &mut args);
+    if let Some(a) = q.action {
+        args.push(a.to_uppercase().into());
+        clauses.push(format!("action = ?{}", args.len()));
+    }
+    if let Some(r) = q.exit_reason {
+        args.push(r.into());
+        clauses.push(format!("exit_reason = ?{}", args.len()));
+    }
+    if let Some(id) = q.run_id {
+        args.push(id.into());
+        clauses.push(format!("run_id = ?{}", args.len()));
+    }
+    let sql = format!(
+        "SELECT id, ts, ts_ms, action, side, size_btc, entry_px, exit_px, notional_usd, pnl_usd,
+                hl_pnl, hl_fee, leader, partial, tp_index, exit_reason, run_id
+         FROM trades {} ORDER BY ts_ms DESC, id DESC LIMIT {} OFFSET {}",
+        where_sql(&clauses),
+        q.limit.unwrap_or(200).min(5000),
+        q.offset.unwrap_or(0),
+    );
+    db.with(|c| {
+        let mut stmt = c.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(args), |r| {
+            Ok(TradeRow {
+                id: r.get(0)?,
+                ts: r.get(1)?,
+                ts_ms: r.get(2)?,
+                action: r.get(3)?,
+                side: r.get(4)?,
+                size_btc: r.get(5)?,
+                entry_px: r.get(6)?,
+                exit_px: r.get(7)?,
+                notional_usd: r.get(8)?,
+                pnl_usd: r.get(9)?,
+                hl_pnl: r.get(10)?,
+                hl_fee: r.get(11)?,
+                leader: r.get(12)?,
+                partial: r.get(13)?,
+                tp_index: r.get(14)?,
+                exit_reason: r.get(15)?,
+                run_id: r.get(16)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReasonStats {
+    pub exit_reason: String,
+    pub closes: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub pnl_usd: f64,
+}
+
+/// Realised PnL per close reason (stop_out, tp1, runner_exit, manual, ...).
+#[tauri::command]
+pub fn pnl_by_exit_reason(db: State<Db>, from: Option<i64>, to: Option<i64>) -> Result<Vec<ReasonStats>, String> {
+    let mut clauses = vec!["action = 'CLOSE'".to_string()];
+    let mut args = Vec::new();
+    time_filter(from, to, &mut clauses, &mut args);
+    let sql = format!(
+        "SELECT COALESCE(exit_reason, 'unknown'), COUNT(*),
+                SUM(CASE WHEN pnl_usd > 0 THEN 1 ELSE 0 END),
+                SUM(CASE WHEN pnl_usd < 0 THEN 1 ELSE 0 END),
+                COALESCE(SUM(pnl_usd), 0)
+         FROM trades {} GROUP BY 1 ORDER BY 5 ASC",
+        where_sql(&clauses)
+    );
+    db.with(|c| {
+        let mut stmt = c.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(args), |r| {
+            Ok(ReasonStats {
+                exit_reason: r.get(0)?,
+                closes: r.get(1)?,
+                wins: r.get(2)?,
+                losses: r.get(3)?,
+                pnl_usd: r.get(4)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeSummary {
+    pub opens: i64,
+    pub closes: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub win_rate: Option<f64>,
+    pub pnl_usd: f64,
+    pub fees_usd: f64,
+    pub best_usd: Option<f64>,
+    pub worst_usd: Option<f64>,
+}
+
+/// Headline numbers for the Dashboard over a time window.
+#[tauri::command]
+pub fn trade_summary(db: State<Db>, from: Option<i64>, to: Option<i64>) -> Result<TradeSummary, String> {
+    let mut clauses = Vec::new();
+    let mut args = Vec::new();
+    time_filter(from, to, &mut clauses, &mut args);
+    let sql = format!(
+        "SELECT SUM(action = 'OPEN'), SUM(action = 'CLOSE'),
+                SUM(action = 'CLOSE' AND pnl_usd > 0), SUM(action = 'CLOSE' AND pnl_usd < 0),
+                COALESCE(SUM(CASE WHEN action = 'CLOSE' THEN pnl_usd END), 0),
+                COALESCE(SUM(hl_fee), 0),
+                MAX(CASE WHEN action = 'CLOSE' THEN pnl_usd END),
+                MIN(CASE WHEN action = 'CLOSE' THEN pnl_usd END)
+         FROM trades {}",
+        where_sql(&clauses)
+    );
+    db.with(|c| {
+        c.query_row(&sql, params_from_iter(args), |r| {
+            let wins: i64 = r.get::<_, Option<i64>>(2)?.unwrap_or(0);
+            let losses: i64 = r.get::<_, Option<i64>>(3)?.unwrap_or(0);
+            Ok(TradeSummary {
+                opens: r.get::<_, Option<i64>>(0)?.unwrap_or(0),
+                closes: r.get::<_, Option<i64>>(1)?.unwrap_or(0),
+                wins,
+                losses,
+                win_rate: if wins + losses > 0 { Some(wins as f64 / (wins + losses) as f64) } else { None },
+                pnl_usd: r.get(4)?,
+                fees_usd: r.get(5)?,
+                best_usd: r.get(6)?,
+                worst_usd: r.get(7)?,
+            })
+        })
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRow {
+    pub id: i64,
+    pub run_id: Option<i64>,
+    pub ts_ms: i64,
+    pub event: serde_json::Value,
+}
+
+/// Stored bot events, newest first. `types` filters by event type (signal, halt, ...).
+#[tauri::command]
+pub fn query_events(
+    db: State<Db>,
+    run_id: Option<i64>,
+    types: Option<Vec<String>>,
+    limit: Option<u32>,
+) -> Result<Vec<EventRow>, String> {
+    let mut clauses = Vec::new();
+    let mut args: Vec<rusqlite::types::Value> = Vec::new();
+    if let Some(id) = run_id {
+        args.push(id.into());
+        clauses.push(format!("run_id = ?{}", args.len()));
+    }
+    if let Some(types) = types.filter(|t| !t.is_empty()) {
+        let mut marks = Vec::new();
+        for t in types {
+            args.push(t.into());
+            marks.push(format!("?{}", args.len()));
+        }
+        clauses.push(format!("type IN ({})", marks.join(", ")));
+    }
+    let sql = format!(
+        "SELECT id, run_id, ts_ms, payload FROM events {} ORDER BY ts_ms DESC, id DESC LIMIT {}",
+        where_sql(&clauses),
+        limit.unwrap_or(200).min(5000)
+    );
+    db.with(|c| {
+        let mut stmt = c.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(args), |r| {
+            let payload: String = r.get(3)?;
+            Ok(EventRow {
+                id: r.get(0)?,
+                run_id: r.get(1)?,
+                ts_ms: r.get(2)?,
+                event: serde_json::from_str(&payload).unwrap_or(serde_json::Value::String(payload)),
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRow {
+    pub id: i64,
+    pub started_ms: i64,
+    pub stopped_ms: Option<i64>,
+    pub exit_code: Option<i32>,
+    pub exit_kind: Option<String>,
+    pub app_version: Option<String>,
+    pub bot_version: Option<String>,
+    pub node_version: Option<String>,
+    pub config_hash: Option<String>,
+    pub trades: i64,
+    pub closes: i64,
+    pub wins: i64,
+    pub pnl_usd: f64,
+}
+
+/// Supervisor runs, newest first, with the trades attributed to each.
+#[tauri::command]
+pub fn list_runs(db: State<Db>, limit: Option<u32>) -> Result<Vec<RunRow>, String> {
+    db.with(|c| {
+        let mut stmt = c.prepare(
+            "SELECT r.id, r.started_ms, r.stopped_ms, r.exit_code, r.exit_kind, r.app_version,
+                    r.bot_version, r.node_version, r.config_hash,
+                    COUNT(t.id),
+                    COALESCE(SUM(t.action = 'CLOSE'), 0),
+                    COALESCE(SUM(t.action = 'CLOSE' AND t.pnl_usd > 0), 0),
+                    COALESCE(SUM(CASE WHEN t.action = 'CLOSE' THEN t.pnl_usd END), 0)
+             FROM runs r LEFT JOIN trades t ON t.run_id = r.id
+             GROUP BY r.id ORDER BY r.id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map([limit.unwrap_or(100)], |r| {
+            Ok(RunRow {
+                id: r.get(0)?,
+                started_ms: r.get(1)?,
+                stopped_ms: r.get(2)?,
+                exit_code: r.get(3)?,
+                exit_kind: r.get(4)?,
+                app_version: r.get(5)?,
+                bot_version: r.get(6)?,
+                node_version: r.get(7)?,
+                config_hash: r.get(8)?,
+                trades: r.get(9)?,
+                closes: r.get(10)?,
+                wins: r.get(11)?,
+                pnl_usd: r.get(12)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// The config.json a run was started with.
+#[tauri::command]
+pub fn get_run_config(db: State<Db>, run_id: i64) -> Result<serde_json::Value, String> {
+    let snapshot: Option<String> = db.with(|c| {
+        c.query_row("SELECT config_snapshot FROM runs WHERE id = ?1", [run_id], |r| r.get(0))
+            .optional()
+            .map(Option::flatten)
+    })?;
+    let snapshot = snapshot.ok_or_else(|| format!("No config recorded for run {}", run_id))?;
+    serde_json::from_str(&snapshot).map_err(|e| format!("Stored config is not valid JSON: {}", e))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPerformance {
+    pub config_hash: String,
+    pub runs: i64,
+    pub first_started_ms: i64,
+    pub last_started_ms: i64,
+    pub run_time_ms: i64,
+    pub closes: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub win_rate: Option<f64>,
+    pub pnl_usd: f64,
+}
+
+/// Realised results grouped by the config fingerprint the trades ran under.
+#[tauri::command]
+pub fn performance_by_config(db: State<Db>) -> Result<Vec<ConfigPerformance>, String> {
+    db.with(|c| {
+        let mut stmt = c.prepare(
+            "SELECT r.config_hash, COUNT(DISTINCT r.id), MIN(r.started_ms), MAX(r.started_ms),
+                    (SELECT COALESCE(SUM(COALESCE(r2.stopped_ms, ?1) - r2.started_ms), 0)
+                     FROM runs r2 WHERE r2.config_hash = r.config_hash),
+                    COALESCE(SUM(t.action = 'CLOSE'), 0),
+                    COALESCE(SUM(t.action = 'CLOSE' AND t.pnl_usd > 0), 0),
+                    COALESCE(SUM(t.action = 'CLOSE' AND t.pnl_usd < 0), 0),
+                    COALESCE(SUM(CASE WHEN t.action = 'CLOSE' THEN t.pnl_usd END), 0)
+             FROM runs r LEFT JOIN trades t ON t.run_id = r.id
+             WHERE r.config_hash IS NOT NULL
+             GROUP BY r.config_hash ORDER BY MAX(r.started_ms) DESC",
+        )?;
+        let rows = stmt.query_map([now_ms()], |r| {
+            let wins: i64 = r.get(6)?;
+            let losses: i64 = r.get(7)?;
+            Ok(ConfigPerformance {
+                config_hash: r.get(0)?,
+                runs: r.get(1)?,
+                first_started_ms: r.get(2)?,
+                last_started_ms: r.get(3)?,
+                run_time_ms: r.get(4)?,
+                closes: r.get(5)?,
+                wins,
+                losses,
+                win_rate: if wins + losses > 0 { Some(wins as f64 / (wins + losses) as f64) } else { None },
+                pnl_usd: r.get(8)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
@@ -0,0 +1,413 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::audit_log;
+use crate::fsutil::{self, WriteOptions};
+
+// --- .env File ---
+//
+// Parsed line by line so comments, blank lines and key order survive a round trip.
+// Lines that aren't touched are written back byte for byte; only set keys are
+// re-serialized. Values are read the way dotenv 17, which the bot loads .env with,
+// reads them: optional `export `, an unquoted value ends at the first `#`, quotes
+// are stripped without unescaping anything, and only double quotes expand `\n` and
+// `\r`. Backslashes are otherwise literal, so Windows paths are written in single
+// quotes. The last definition of a key wins.
+
+pub const ENV_FILE: &str = ".env";
+const HEADER: &str = "# HL Signalbot - generated by setup wizard";
+const REDACTED: &str = "********";
+
+/// Keys the bot reads, and whether their value is a credential.
+const KNOWN_KEYS: &[(&str, bool)] = &[
+    ("HL_WALLET_ADDRESS", false),
+    ("HL_PRIVATE_KEY", true),
+    ("HL_PRIVATE_KEY_PATH", false),
+    ("TG_ENABLED", false),
+    ("TG_CHAT", false),
+    ("TG_TOKEN", true),
+    ("TG_TOKEN_PATH", false),
+];
+
+#[derive(Debug, Clone)]
+enum EnvLine {
+    /// Comment, blank or unparseable line, kept verbatim.
+    Raw(String),
+    Entry { key: String, value: String, raw: Option<String> },
+}
+
+#[derive(Debug, Default)]
+pub struct EnvFile {
+    lines: Vec<EnvLine>,
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+const QUOTES: [char; 3] = ['\'', '"', '`'];
+
+/// Byte index of the quote closing a value that opens with `q`. As in dotenv's
+/// pattern, a backslash only stops the quote right after it from closing.
+fn closing_quote(raw: &str, q: char) -> Option<usize> {
+    let mut chars = raw.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next_if(|&(_, n)| n == q);
+        } else if c == q {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Strip one pair of matching outer quotes, then expand `\n`/`\r` if the value
+/// started with a double quote. Nothing else is unescaped.
+fn unquote(value: &str) -> String {
+    let first = value.chars().next();
+    let inner = match first {
+        Some(q) if QUOTES.contains(&q) && value.len() >= 2 && value.ends_with(q) => &value[1..value.len() - 1],
+        _ => value,
+    };
+    if first == Some('"') {
+        inner.replace("\\n", "\n").replace("\\r", "\r")
+    } else {
+        inner.to_string()
+    }
+}
+
+fn parse_value(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(q) = raw.chars().next().filter(|c| QUOTES.contains(c)) {
+        // A quoted value may only be followed by a comment
+        if let Some(end) = closing_quote(raw, q) {
+            let rest = raw[end + 1..].trim_start();
+            if rest.is_empty() || rest.starts_with('#') {
+                return unquote(&raw[..=end]);
+            }
+        }
+    }
+    // Otherwise the value runs to the first `#`
+    let value = raw.split('#').next().unwrap_or_default().trim();
+    unquote(value)
+}
+
+/// The shortest spelling that `parse_value` reads back as `value`, or `None` if the
+/// value can't be written on one line.
+fn serialize_value(value: &str) -> Option<String> {
+    if value.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:@~+,".contains(c)) {
+        return Some(value.to_string());
+    }
+    let candidates = [format!("'{}'", value), format!("`{}`", value), value.to_string()];
+    let double = (!value.contains("\\n") && !value.contains("\\r")).then(|| format!("\"{}\"", value));
+    candidates
+        .into_iter()
+        .chain(double)
+        .find(|s| !s.contains(['\n', '\r']) && parse_value(s) == value)
+}
+
+impl EnvFile {
+    pub fn parse(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                let body = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+                match body.split_once('=') {
+                    Some((k, v)) if !trimmed.starts_with('#') && is_valid_key(k.trim()) => EnvLine::Entry {
+                        key: k.trim().to_string(),
+                        value: parse_value(v),
+                        raw: Some(line.to_string()),
+                    },
+                    _ => EnvLine::Raw(line.to_string()),
+                }
+            })
+            .collect();
+        Self { lines }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|l| match l {
+            EnvLine::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Replace the first definition of `key` in place (dropping any other duplicates)
+    /// or append it.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let mut found = false;
+        self.lines.retain_mut(|l| match l {
+            EnvLine::Entry { key: k, value: v, raw } if k == key => {
+                if found {
+                    return false;
+                }
+                found = true;
+                if v != value {
+                    *v = value.to_string();
+                    *raw = None;
+                }
+                true
+            }
+            _ => true,
+        });
+        if !found {
+            if self.lines.is_empty() {
+                self.lines.push(EnvLine::Raw(HEADER.into()));
+                self.lines.push(EnvLine::Raw(String::new()));
+            }
+            self.lines.push(EnvLine::Entry { key: key.into(), value: value.into(), raw: None });
+        }
+    }
+
+    pub fn unset(&mut self, key: &str) -> bool {
+        let before = self.lines.len();
+        self.lines.retain(|l| !matches!(l, EnvLine::Entry { key: k, .. } if k == key));
+        self.lines.len() != before
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|l| match l {
+            EnvLine::Entry { key, value, .. } => Some((key.as_str(), value.as_str())),
+            _ => None,
+        })
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut out: String = self
+            .lines
+            .iter()
+            .map(|l| match l {
+                EnvLine::Raw(raw) | EnvLine::Entry { raw: Some(raw), .. } => raw.clone(),
+                // `validate` refuses values without a spelling
+                EnvLine::Entry { key, value, raw: None } => {
+                    format!("{}={}", key, serialize_value(value).unwrap_or_else(|| format!("'{}'", value)))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        out.push('\n');
+        out
+    }
+}
+
+/// Credentials: the known secret keys plus anything that looks like one.
+pub fn is_secret_key(key: &str) -> bool {
+    if let Some((_, secret)) = KNOWN_KEYS.iter().find(|(k, _)| *k == key) {
+        return *secret;
+    }
+    let upper = key.to_ascii_uppercase();
+    !upper.ends_with("_PATH") && ["KEY", "TOKEN", "SECRET", "PASSWORD", "MNEMONIC"].iter().any(|s| upper.contains(s))
+}
+
+/// Check one key/value pair. Known keys get format checks; any value must fit on one line.
+pub fn validate(key: &str, value: &str) -> Result<(), String> {
+    if !is_valid_key(key) {
+        return Err(format!("'{}' is not a valid variable name", key));
+    }
+    if value.chars().any(|c| c.is_control()) {
+        return Err(format!("{}: value must be a single line", key));
+    }
+    if serialize_value(value).is_none() {
+        return Err(format!("{}: value can't be written to .env as is", key));
+    }
+    let ok = match key {
+        "HL_WALLET_ADDRESS" => return crate::wallet::parse_address(value).map(|_| ()).map_err(|e| format!("{}: {}", key, e)),
+        "HL_PRIVATE_KEY" => return crate::wallet::parse_private_key(value).map(|_| ()).map_err(|e| format!("{}: {}", key, e)),
+        "HL_PRIVATE_KEY_PATH" | "TG_TOKEN_PATH" => !value.trim().is_empty(),
+        "TG_ENABLED" => ["true", "false", "1", "0", "yes", "no"].contains(&value.to_ascii_lowercase().as_str()),
+        "TG_CHAT" => {
+            value.strip_prefix('@').is_some_and(|n| n.len() >= 4 && n.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+                || value.strip_prefix('-').unwrap_or(value).parse::<i64>().is_ok()
+        }
+        "TG_TOKEN" => value
+            .split_once(':')
+            .is_some_and(|(id, secret)| id.parse::<u64>().is_ok() && secret.len() >= 30 && secret.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')),
+        _ => true,
+    };
+    if ok {
+        return Ok(());
+    }
+    Err(match key {
+        "TG_ENABLED" => "TG_ENABLED: must be true or false".into(),
+        "TG_CHAT" => "TG_CHAT: must be an @channel name or a numeric chat id".into(),
+        "TG_TOKEN" => "TG_TOKEN: must look like 123456789:ABC... (from @BotFather)".into(),
+        _ => format!("{}: must not be empty", key),
+    })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvEntry {
+    pub key: String,
+    /// `None` for secrets; see `redacted`.
+    pub value: Option<String>,
+    pub secret: bool,
+    /// Masked placeholder shown instead of a secret value.
+    pub redacted: Option<String>,
+    pub known: bool,
+}
+
+fn entry(key: &str, value: &str) -> EnvEntry {
+    let secret = is_secret_key(key);
+    EnvEntry {
+        key: key.to_string(),
+        value: if secret { None } else { Some(value.to_string()) },
+        secret,
+        redacted: secret.then(|| REDACTED.to_string()),
+        known: KNOWN_KEYS.iter().any(|(k, _)| *k == key),
+    }
+}
+
+fn env_path() -> Result<PathBuf, String> {
+    Ok(crate::file_access::resolve(ENV_FILE, crate::file_access::Access::Exists).map_err(|e| e.to_string())?.path)
+}
+
+/// Apply `set` and `unset` to .env in one atomic write. Every value is validated
+/// first; nothing is written if any is rejected.
+pub fn update(path: &Path, set: &BTreeMap<String, String>, unset: &[String]) -> Result<(), String> {
+    let errors: Vec<String> = set.iter().filter_map(|(k, v)| validate(k, v.trim()).err()).collect();
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    let mut env = EnvFile::load(path)?;
+    for key in unset {
+        env.unset(key);
+    }
+    for (key, value) in set {
+        env.set(key, value.trim());
+    }
+    fsutil::write_file(path, env.serialize(), WriteOptions::SECRET)
+}
+
+// --- Commands ---
+
+/// All variables in .env, in file order, with secret values redacted.
+#[tauri::command]
+pub fn get_env() -> Result<Vec<EnvEntry>, String> {
+    let env = EnvFile::load(&env_path()?)?;
+    Ok(env.entries().map(|(k, v)| entry(k, v)).collect())
+}
+
+#[tauri::command]
+pub fn get_env_var(key: String) -> Result<Option<EnvEntry>, String> {
+    let env = EnvFile::load(&env_path()?)?;
+    Ok(env.get(&key).map(|v| entry(&key, v)))
+}
+
+/// Audit params for an update, with secret values masked.
+fn audit_params(set: &BTreeMap<String, String>, unset: &[String]) -> serde_json::Value {
+    let set: serde_json::Map<String, serde_json::Value> =
+        set.iter().map(|(k, v)| (k.clone(), audit_log::mask(v, is_secret_key(k)))).collect();
+    serde_json::json!({ "set": set, "unset": unset })
+}
+
+#[tauri::command]
+pub fn set_env_var(app: tauri::AppHandle, key: String, value: String) -> Result<(), String> {
+    let set = BTreeMap::from([(key, value)]);
+    let params = audit_params(&set, &[]);
+    let result = env_path().and_then(|path| update(&path, &set, &[]));
+    audit_log::record(&app, "set_env_var", params, result)
+}
+
+#[tauri::command]
+pub fn unset_env_var(app: tauri::AppHandle, key: String) -> Result<(), String> {
+    let unset = [key];
+    let params = audit_params(&BTreeMap::new(), &unset);
+    let result = env_path().and_then(|path| update(&path, &BTreeMap::new(), &unset));
+    audit_log::record(&app, "unset_env_var", params, result)
+}
+
+/// Set and unset several variables in one write, e.g. when the setup wizard saves.
+#[tauri::command]
+pub fn update_env(app: tauri::AppHandle, set: Option<BTreeMap<String, String>>, unset: Option<Vec<String>>) -> Result<(), String> {
+    let (set, unset) = (set.unwrap_or_default(), unset.unwrap_or_default());
+    let params = audit_params(&set, &unset);
+    let result = env_path().and_then(|path| update(&path, &set, &unset));
+    audit_log::record(&app, "update_env", params, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untouched_lines_round_trip_verbatim() {
+        let text = "# comment\n\nexport HL_WALLET_ADDRESS=0xabc  # inline\nTG_CHAT = \"@channel\"\nnot a variable\n";
+        assert_eq!(EnvFile::parse(text).serialize(), text);
+    }
+
+    #[test]
+    fn set_values_read_back_unchanged() {
+        let values = [
+            "plain",
+            r"C:\Users\me\.config\hl-signalbot\key.txt",
+            "with spaces",
+            "has # hash",
+            "it's",
+            r#"say "hi""#,
+            r"literal \n not a newline",
+            "`tick` and 'single'",
+            r#"\"escaped\""#,
+            "",
+        ];
+        let mut env = EnvFile::parse("KEEP=1 # untouched\n");
+        for (i, value) in values.iter().enumerate() {
+            assert!(validate(&format!("V{}", i), value).is_ok(), "{:?} should be writable", value);
+            env.set(&format!("V{}", i), value);
+        }
+        let reread = EnvFile::parse(&env.serialize());
+        assert_eq!(reread.get("KEEP"), Some("1"));
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(reread.get(&format!("V{}", i)), Some(*value));
+        }
+        assert!(env.serialize().starts_with("KEEP=1 # untouched\n"));
+    }
+
+    #[test]
+    fn reads_values_like_dotenv() {
+        let env = EnvFile::parse(concat!(
+            "A=\"one\\ntwo\"\n",
+            "B='one\\ntwo'\n",
+            "C=value # comment\n",
+            "D=\"quoted\" # comment\n",
+            "E=\"quoted\" trailing\n",
+            "F=first\n",
+            "F=second\n",
+            "export G=exported\n",
+        ));
+        assert_eq!(env.get("A"), Some("one\ntwo"));
+        assert_eq!(env.get("B"), Some("one\\ntwo"));
+        assert_eq!(env.get("C"), Some("value"));
+        assert_eq!(env.get("D"), Some("quoted"));
+        assert_eq!(env.get("E"), Some("\"quoted\" trailing"));
+        assert_eq!(env.get("F"), Some("second"));
+        assert_eq!(env.get("G"), Some("exported"));
+    }
+
+    #[test]
+    fn set_replaces_duplicates_and_unset_removes_all() {
+        let mut env = EnvFile::parse("A=1\nB=2\nA=3\n");
+        env.set("A", "4");
+        assert_eq!(env.serialize(), "A=4\nB=2\n");
+        assert!(env.unset("A"));
+        assert!(!env.unset("A"));
+        assert_eq!(env.serialize(), "B=2\n");
+    }
+
+    #[test]
+    fn rejects_values_without_a_spelling() {
+        assert!(validate("X", "line\nbreak").is_err());
+        assert!(validate("X", "'\"` # all three").is_err());
+    }
+}
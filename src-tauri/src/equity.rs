@@ -0,0 +1,249 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+// --- Equity Store ---
+//
+// Append-only file of fixed-size little-endian records:
+//   ts_ms: i64 | equity: f64 | daily_pnl: f64 (NaN when not yet reported)
+// 24 bytes per bot poll keeps months of history in a few MB, and a torn trailing
+// record from a crash is simply ignored on read.
+
+const RECORD_LEN: usize = 24;
+const DEFAULT_RESOLUTION: usize = 500;
+const MAX_RESOLUTION: usize = 5000;
+
+pub fn equity_store_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("equity.dat")
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    ts_ms: i64,
+    equity: f64,
+    daily_pnl: f64,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn append_sample(path: &Path, s: &Sample) -> std::io::Result<()> {
+    let mut buf = [0u8; RECORD_LEN];
+    buf[0..8].copy_from_slice(&s.ts_ms.to_le_bytes());
+    buf[8..16].copy_from_slice(&s.equity.to_le_bytes());
+    buf[16..24].copy_from_slice(&s.daily_pnl.to_le_bytes());
+    let mut f = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    f.write_all(&buf)
+}
+
+fn read_samples(path: &Path) -> Result<Vec<Sample>, String> {
+    let bytes = match std::fs::read(path) {
+        Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let f64_at = |c: &[u8], i: usize| f64::from_le_bytes(c[i..i + 8].try_into().unwrap());
+    Ok(bytes
+        .chunks_exact(RECORD_LEN)
+        .map(|c| Sample {
+            ts_ms: i64::from_le_bytes(c[0..8].try_into().unwrap()),
+            equity: f64_at(c, 8),
+            daily_pnl: f64_at(c, 16),
+        })
+        .collect())
+}
+
+/// Most recently recorded equity, if any.
+pub fn latest_equity(data_dir: &Path) -> Option<f64> {
+    read_samples(&equity_store_path(data_dir))
+        .ok()?
+        .into_iter()
+        .max_by_key(|s| s.ts_ms)
+        .map(|s| s.equity)
+}
+
+/// Fed every stdout line from the bot. The bot reports `pnl` and `equity` as separate
+/// events each poll; one sample is written per `equity` event carrying the latest pnl.
+pub struct EquityRecorder {
+    path: PathBuf,
+    last_pnl: f64,
+}
+
+impl EquityRecorder {
+    pub fn new(data_dir: &Path) -> Self {
+        Self { path: equity_store_path(data_dir), last_pnl: f64::NAN }
+    }
+
+    pub fn observe(&mut self, event: &serde_json::Value) {
+        let value = event["value"].as_f64();
+        match event["type"].as_str() {
+            Some("pnl") => {
+                if let Some(v) = value {
+                    self.last_pnl = v;
+                }
+            }
+            Some("equity") => {
+                // The bot reports 0 when the balance lookup fails — not a real sample
+                let Some(v) = value.filter(|v| v.is_finite() && *v > 0.0) else { return };
+                let sample = Sample { ts_ms: now_ms(), equity: v, daily_pnl: self.last_pnl };
+                if let Err(e) = append_sample(&self.path, &sample) {
+                    log::warn!("Failed to record equity sample: {}", e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// --- Series Query ---
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquityPoint {
+    pub ts: i64,
+    pub equity: f64,
+    pub daily_pnl: Option<f64>,
+    pub drawdown_usd: f64,
+    pub drawdown_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquitySeries {
+    pub from: Option<i64>,
+    pub to: i64,
+    pub sample_count: usize,
+    pub peak_equity: Option<f64>,
+    pub max_drawdown_usd: f64,
+    pub max_drawdown_pct: f64,
+    pub points: Vec<EquityPoint>,
+}
+
+/// Parse a range like "24h", "7d", "4w" or "all" into a lookback in milliseconds.
+fn parse_range(range: &str) -> Result<Option<i64>, String> {
+    let r = range.trim().to_lowercase();
+    if r.is_empty() || r == "all" {
+        return Ok(None);
+    }
+    let unit = r.chars().last().unwrap_or('d');
+    let num = &r[..r.len() - unit.len_utf8()];
+    let n: i64 = num.parse().map_err(|_| format!("Invalid range '{}'", range))?;
+    let unit_ms = match unit {
+        'h' => 3_600_000,
+        'd' => 86_400_000,
+        'w' => 7 * 86_400_000,
+        _ => return Err(format!("Invalid range '{}' (use e.g. 24h, 7d, 4w or all)", range)),
+    };
+    if n <= 0 {
+        return Err(format!("Invalid range '{}'", range));
+    }
+    n.checked_mul(unit_ms).map(Some).ok_or_else(|| format!("Range '{}' is too long", range))
+}
+
+/// Min/max bucket downsampling: each time bucket keeps its lowest and highest equity
+/// and its deepest drawdown, so dips survive no matter how coarse the chart is.
+fn downsample(points: Vec<EquityPoint>, resolution: usize) -> Vec<EquityPoint> {
+    if points.len() <= resolution {
+        return points;
+    }
+    let buckets = (resolution / 3).max(1);
+    let first = points[0].ts;
+    let span = (points[points.len() - 1].ts - first).max(1) as f64;
+    let bucket_of = |ts: i64| ((((ts - first) as f64 / span) * buckets as f64) as usize).min(buckets - 1);
+
+    let mut out = Vec::with_capacity(buckets * 3);
+    for chunk in points.chunk_by(|a, b| bucket_of(a.ts) == bucket_of(b.ts)) {
+        let pick = |better: fn(&EquityPoint, &EquityPoint) -> bool| {
+            (0..chunk.len()).fold(0, |best, i| if better(&chunk[i], &chunk[best]) { i } else { best })
+        };
+        let mut picks = [
+            pick(|a, b| a.equity < b.equity),
+            pick(|a, b| a.equity > b.equity),
+            pick(|a, b| a.drawdown_usd > b.drawdown_usd),
+        ];
+        picks.sort_unstable();
+        let mut last = None;
+        for i in picks {
+            if last != Some(i) {
+                out.push(chunk[i].clone());
+                last = Some(i);
+            }
+        }
+    }
+    out
+}
+
+fn build_series(samples: &[Sample], range: &str, resolution: usize, now: i64) -> Result<EquitySeries, String> {
+    let from = parse_range(range)?.map(|lookback| now - lookback);
+
+    // Drawdown runs over the full history so the peak reflects the all-time high,
+    // not just the high inside the requested window.
+    let mut peak = f64::MIN;
+    let mut max_dd_usd: f64 = 0.0;
+    let mut max_dd_pct: f64 = 0.0;
+    let mut peak_in_range: Option<f64> = None;
+    let mut points = Vec::new();
+    for s in samples {
+        peak = peak.max(s.equity);
+        let dd_usd = peak - s.equity;
+        let dd_pct = if peak > 0.0 { dd_usd / peak } else { 0.0 };
+        if from.is_some_and(|f| s.ts_ms < f) {
+            continue;
+        }
+        max_dd_usd = max_dd_usd.max(dd_usd);
+        max_dd_pct = max_dd_pct.max(dd_pct);
+        peak_in_range = Some(peak);
+        points.push(EquityPoint {
+            ts: s.ts_ms,
+            equity: s.equity,
+            daily_pnl: if s.daily_pnl.is_nan() { None } else { Some(s.daily_pnl) },
+            drawdown_usd: dd_usd,
+            drawdown_pct: dd_pct,
+        });
+    }
+
+    let sample_count = points.len();
+    Ok(EquitySeries {
+        from,
+        to: now,
+        sample_count,
+        peak_equity: peak_in_range,
+        max_drawdown_usd: max_dd_usd,
+        max_drawdown_pct: max_dd_pct,
+        points: downsample(points, resolution),
+    })
+}
+
+/// Equity history for charting. `range` is a lookback ("24h", "7d", "4w", "all");
+/// `resolution` caps the number of returned points.
+#[tauri::command]
+pub async fn get_equity_series(range: String, resolution: Option<usize>) -> Result<EquitySeries, String> {
+    let path = equity_store_path(&crate::user_data_dir()?);
+    let resolution = resolution.unwrap_or(DEFAULT_RESOLUTION).clamp(3, MAX_RESOLUTION);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut samples = read_samples(&path)?;
+        samples.sort_by_key(|s| s.ts_ms);
+        build_series(&samples, &range, resolution, now_ms())
+    })
+    .await
+    .map_err(|e| format!("Equity query failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("all"), Ok(None));
+        assert_eq!(parse_range(" 24H "), Ok(Some(24 * 3_600_000)));
+        assert_eq!(parse_range("7d"), Ok(Some(7 * 86_400_000)));
+        assert!(parse_range("0d").is_err());
+        assert!(parse_range("5y").is_err());
+        assert_eq!(parse_range("999999999999999w"), Err("Range '999999999999999w' is too long".to_string()));
+    }
+}
@@ -0,0 +1,307 @@
+use std::path::{Path, PathBuf};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+// --- File Access Policy ---
+//
+// The frontend may only name files from a fixed list of logical files, and every
+// resolved path must stay inside the canonical user data dir. A symlink is followed
+// only if it lands back inside that dir, so a planted link can't redirect a write
+// (or a read of a secret) somewhere else.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicalFile {
+    Config,
+    Env,
+    Trades,
+    State,
+    License,
+    PrivateKey,
+    Keystore,
+    TelegramToken,
+    Profile,
+}
+
+struct FileRule {
+    file: LogicalFile,
+    name: &'static str,
+    read: bool,
+    write: bool,
+    /// Written owner-only (0600).
+    secret: bool,
+}
+
+const RULES: &[FileRule] = &[
+    FileRule { file: LogicalFile::Config, name: "config.json", read: true, write: true, secret: false },
+    // Goes through the typed .env commands, which validate keys and redact secrets
+    FileRule { file: LogicalFile::Env, name: ".env", read: false, write: false, secret: true },
+    FileRule { file: LogicalFile::Trades, name: "trades.jsonl", read: true, write: false, secret: false },
+    FileRule { file: LogicalFile::State, name: "state.json", read: true, write: false, secret: false },
+    // Holds the key and its signed token; the UI asks get_license_status instead
+    FileRule { file: LogicalFile::License, name: "license.json", read: false, write: false, secret: true },
+    // Legacy plaintext key, only ever read by the bot; new keys go into the key store
+    FileRule { file: LogicalFile::PrivateKey, name: "private_key", read: false, write: false, secret: true },
+    // Only the keystore commands touch it
+    FileRule { file: LogicalFile::Keystore, name: "keystore.json", read: false, write: false, secret: true },
+    // The UI can replace it and test it, but never read it back
+    FileRule { file: LogicalFile::TelegramToken, name: "tg_token", read: false, write: true, secret: true },
+];
+
+/// Profiles live under `profiles/<name>.json` and are written by the profile commands.
+const PROFILE_RULE: FileRule =
+    FileRule { file: LogicalFile::Profile, name: "profiles/", read: true, write: false, secret: false };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    /// Existence checks are allowed for any declared file.
+    Exists,
+}
+
+#[derive(Debug)]
+pub enum FileAccessError {
+    /// Not one of the declared logical files.
+    NotAllowed(String),
+    /// The file is declared, but not for this kind of access.
+    Denied { name: String, access: Access },
+    /// The path resolves outside the data dir.
+    OutsideDataDir(PathBuf),
+    /// A symlink on the path points outside the data dir.
+    SymlinkEscape(PathBuf),
+    Io(String),
+    Invalid(String),
+}
+
+impl FileAccessError {
+    fn kind(&self) -> &'static str {
+        match self {
+            FileAccessError::NotAllowed(_) => "notAllowed",
+            FileAccessError::Denied { .. } => "denied",
+            FileAccessError::OutsideDataDir(_) => "outsideDataDir",
+            FileAccessError::SymlinkEscape(_) => "symlinkEscape",
+            FileAccessError::Io(_) => "io",
+            FileAccessError::Invalid(_) => "invalid",
+        }
+    }
+}
+
+impl std::fmt::Display for FileAccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileAccessError::NotAllowed(name) => write!(f, "'{}' is not a file the app manages", name),
+            FileAccessError::Denied { name, access } => write!(f, "{:?} access to '{}' is not allowed", access, name),
+            FileAccessError::OutsideDataDir(p) => write!(f, "{} is outside the app data folder", p.display()),
+            FileAccessError::SymlinkEscape(p) => write!(f, "{} is a link to a location outside the app data folder", p.display()),
+            FileAccessError::Io(msg) | FileAccessError::Invalid(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Serialized as `{ kind, message }` so the UI can branch on the kind and still
+/// show `message` like any other command error.
+impl Serialize for FileAccessError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("FileAccessError", 2)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.end()
+    }
+}
+
+impl From<String> for FileAccessError {
+    fn from(msg: String) -> Self {
+        FileAccessError::Invalid(msg)
+    }
+}
+
+/// A path the policy has approved.
+#[derive(Debug)]
+pub struct ResolvedFile {
+    pub file: LogicalFile,
+    pub path: PathBuf,
+    pub secret: bool,
+}
+
+fn rule_for(name: &str) -> Option<&'static FileRule> {
+    if let Some(profile) = name.strip_prefix(PROFILE_RULE.name) {
+        let stem = profile.strip_suffix(".json")?;
+        let plain = !stem.is_empty() && stem.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_');
+        return plain.then_some(&PROFILE_RULE);
+    }
+    RULES.iter().find(|r| r.name == name)
+}
+
+fn data_root() -> Result<PathBuf, FileAccessError> {
+    let dir = crate::user_data_dir()?;
+    dunce::canonicalize(&dir).map_err(|e| FileAccessError::Io(format!("Cannot resolve {}: {}", dir.display(), e)))
+}
+
+/// Check that `path` can't leave `root`: its nearest existing ancestor must
+/// canonicalize inside, and if the file itself is a symlink its target must too.
+/// Returns the path to use.
+fn confine(root: &Path, path: &Path) -> Result<PathBuf, FileAccessError> {
+    if path.components().any(|c| c == std::path::Component::ParentDir) {
+        return Err(FileAccessError::OutsideDataDir(path.to_path_buf()));
+    }
+    let mut ancestor = path.parent().ok_or_else(|| FileAccessError::OutsideDataDir(path.to_path_buf()))?;
+    while !ancestor.exists() {
+        ancestor = ancestor.parent().ok_or_else(|| FileAccessError::OutsideDataDir(path.to_path_buf()))?;
+    }
+    let canonical = dunce::canonicalize(ancestor)
+        .map_err(|e| FileAccessError::Io(format!("Cannot resolve {}: {}", ancestor.display(), e)))?;
+    if !canonical.starts_with(root) {
+        // Lexically inside but resolving elsewhere means a linked folder on the way
+        return Err(if path.starts_with(root) {
+            FileAccessError::SymlinkEscape(path.to_path_buf())
+        } else {
+            FileAccessError::OutsideDataDir(path.to_path_buf())
+        });
+    }
+
+    if path.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+        let target = dunce::canonicalize(path).map_err(|_| FileAccessError::SymlinkEscape(path.to_path_buf()))?;
+        if !target.starts_with(root) {
+            return Err(FileAccessError::SymlinkEscape(path.to_path_buf()));
+        }
+    }
+    Ok(path.to_path_buf())
+}
+
+/// Resolve a logical file name from the frontend, e.g. `config.json`.
+pub fn resolve(name: &str, access: Access) -> Result<ResolvedFile, FileAccessError> {
+    resolve_in(&data_root()?, name, access)
+}
+
+/// `resolve` against the canonical data dir `root`.
+fn resolve_in(root: &Path, name: &str, access: Access) -> Result<ResolvedFile, FileAccessError> {
+    let rule = rule_for(name).ok_or_else(|| FileAccessError::NotAllowed(name.to_string()))?;
+    let allowed = match access {
+        Access::Read => rule.read,
+        Access::Write => rule.write,
+        Access::Exists => true,
+    };
+    if !allowed {
+        return Err(FileAccessError::Denied { name: name.to_string(), access });
+    }
+    let path = confine(root, &root.join(name))?;
+    Ok(ResolvedFile { file: rule.file, path, secret: rule.secret })
+}
+
+/// Resolve an absolute path from the frontend. It must name a declared file directly
+/// inside the data dir.
+pub fn resolve_path(path: &str, access: Access) -> Result<ResolvedFile, FileAccessError> {
+    resolve_path_in(&data_root()?, path, access)
+}
+
+fn resolve_path_in(root: &Path, path: &str, access: Access) -> Result<ResolvedFile, FileAccessError> {
+    let requested = Path::new(path);
+    let parent = requested.parent().and_then(|p| dunce::canonicalize(p).ok());
+    if parent.as_deref() != Some(root) {
+        return Err(FileAccessError::OutsideDataDir(requested.to_path_buf()));
+    }
+    let name = requested.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    resolve_in(root, &name, access)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A canonical data dir and a sibling folder outside it.
+    fn dirs(name: &str) -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(format!("hl-signalbot-files-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(base.join("data")).unwrap();
+        std::fs::create_dir_all(base.join("outside")).unwrap();
+        let base = dunce::canonicalize(&base).unwrap();
+        (base.join("data"), base.join("outside"))
+    }
+
+    fn cleanup(root: &Path) {
+        let _ = std::fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    fn resolves_declared_files_inside_the_root() {
+        let (root, _) = dirs("declared");
+        let file = resolve_in(&root, "config.json", Access::Write).unwrap();
+        assert_eq!(file.file, LogicalFile::Config);
+        assert_eq!(file.path, root.join("config.json"));
+        let profile = resolve_in(&root, "profiles/Scalp 1.json", Access::Read).unwrap();
+        assert_eq!(profile.file, LogicalFile::Profile);
+        cleanup(&root);
+    }
+
+    #[test]
+    fn rejects_names_that_leave_the_root() {
+        let (root, outside) = dirs("names");
+        let absolute = outside.join("config.json").to_string_lossy().to_string();
+        for name in ["../config.json", "profiles/../x.json", "profiles/../../x.json", "profiles/a/b.json", absolute.as_str(), "nope.json"] {
+            let err = resolve_in(&root, name, Access::Read).unwrap_err();
+            assert!(matches!(err, FileAccessError::NotAllowed(_)), "{}: {:?}", name, err);
+        }
+        let err = confine(&root, &root.join("..").join("config.json")).unwrap_err();
+        assert!(matches!(err, FileAccessError::OutsideDataDir(_)), "{:?}", err);
+        cleanup(&root);
+    }
+
+    #[test]
+    fn denies_secrets_to_the_ui() {
+        let (root, _) = dirs("denied");
+        for (name, access) in [
+            ("license.json", Access::Read),
+            ("keystore.json", Access::Read),
+            ("keystore.json", Access::Write),
+            ("private_key", Access::Read),
+            ("private_key", Access::Write),
+            ("tg_token", Access::Read),
+            (".env", Access::Read),
+        ] {
+            let err = resolve_in(&root, name, access).unwrap_err();
+            assert!(matches!(err, FileAccessError::Denied { .. }), "{} {:?}: {:?}", name, access, err);
+        }
+        assert!(resolve_in(&root, "keystore.json", Access::Exists).is_ok());
+        cleanup(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_that_point_outside() {
+        use std::os::unix::fs::symlink;
+        let (root, outside) = dirs("symlinks");
+        std::fs::write(outside.join("target.json"), "{}").unwrap();
+
+        symlink(outside.join("target.json"), root.join("config.json")).unwrap();
+        let err = resolve_in(&root, "config.json", Access::Write).unwrap_err();
+        assert!(matches!(err, FileAccessError::SymlinkEscape(_)), "{:?}", err);
+
+        // A dangling link can't be resolved, so it is refused too
+        symlink(outside.join("missing"), root.join("state.json")).unwrap();
+        let err = resolve_in(&root, "state.json", Access::Read).unwrap_err();
+        assert!(matches!(err, FileAccessError::SymlinkEscape(_)), "{:?}", err);
+
+        symlink(&outside, root.join("profiles")).unwrap();
+        let err = resolve_in(&root, "profiles/a.json", Access::Read).unwrap_err();
+        assert!(matches!(err, FileAccessError::SymlinkEscape(_)), "{:?}", err);
+
+        // Links that stay inside are fine
+        std::fs::write(root.join("real.jsonl"), "").unwrap();
+        symlink(root.join("real.jsonl"), root.join("trades.jsonl")).unwrap();
+        assert!(resolve_in(&root, "trades.jsonl", Access::Read).is_ok());
+        cleanup(&root);
+    }
+
+    #[test]
+    fn resolve_path_needs_the_root_as_parent() {
+        let (root, outside) = dirs("paths");
+        std::fs::create_dir_all(root.join("profiles")).unwrap();
+        let ok = resolve_path_in(&root, &root.join("tg_token").to_string_lossy(), Access::Write).unwrap();
+        assert_eq!(ok.file, LogicalFile::TelegramToken);
+        for path in [outside.join("tg_token"), root.join("profiles").join("tg_token"), root.join("..").join("data").join("x").join("tg_token")] {
+            let err = resolve_path_in(&root, &path.to_string_lossy(), Access::Write).unwrap_err();
+            assert!(matches!(err, FileAccessError::OutsideDataDir(_)), "{}: {:?}", path.display(), err);
+        }
+        cleanup(&root);
+    }
+}
@@ -0,0 +1,119 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// --- Atomic File Writes ---
+//
+// New contents go to a temp file in the target's directory, are fsynced, then renamed
+// over the target and the directory is fsynced. A crash or full disk leaves either
+// the old file or the new one, never a truncated mix. Secret files are created with
+// mode 0600 up front, so no byte is ever readable by anyone else.
+
+static TMP_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WriteOptions {
+    /// Owner-only permissions (0600 on Unix), for the file and its backup.
+    pub secret: bool,
+    /// Keep the previous contents as `<name>.bak`.
+    pub backup: bool,
+}
+
+impl WriteOptions {
+    pub const PLAIN: Self = Self { secret: false, backup: false };
+    pub const BACKUP: Self = Self { secret: false, backup: true };
+    pub const SECRET: Self = Self { secret: true, backup: true };
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Mode for the new file: 0600 for secrets, otherwise whatever the file it replaces
+/// had, so a save never loosens permissions someone tightened by hand.
+#[cfg(unix)]
+fn file_mode(path: &Path, secret: bool) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    if secret {
+        return 0o600;
+    }
+    std::fs::metadata(path).map(|m| m.permissions().mode() & 0o777).unwrap_or(0o644)
+}
+
+/// Create a fresh temp file next to `target` and write `contents` to disk through it.
+fn write_temp(target: &Path, contents: &[u8], secret: bool) -> std::io::Result<PathBuf> {
+    let dir = target.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        name,
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(file_mode(target, secret));
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+
+    let result = opts.open(&tmp).and_then(|mut f| {
+        f.write_all(contents)?;
+        f.sync_all()
+    });
+    match result {
+        Ok(()) => Ok(tmp),
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Make the renames in `dir` durable. Directories can't be opened for syncing on
+/// Windows, where the rename is already durable once it returns.
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    std::fs::File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+fn replace(tmp: &Path, target: &Path) -> std::io::Result<()> {
+    std::fs::rename(tmp, target).inspect_err(|_| {
+        let _ = std::fs::remove_file(tmp);
+    })
+}
+
+/// Atomically replace `path` with `contents`.
+pub fn atomic_write(path: &Path, contents: &[u8], opts: WriteOptions) -> std::io::Result<()> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+    if opts.backup {
+        match std::fs::read(path) {
+            Ok(previous) => {
+                let bak = backup_path(path);
+                let tmp = write_temp(&bak, &previous, opts.secret)?;
+                replace(&tmp, &bak)?;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let tmp = write_temp(path, contents, opts.secret)?;
+    replace(&tmp, path)?;
+    sync_dir(dir)
+}
+
+/// `atomic_write` with the error formatted the way commands report it.
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>, opts: WriteOptions) -> Result<(), String> {
+    atomic_write(path, contents.as_ref(), opts).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::process::Command as StdCommand;
+use std::sync::Mutex;
+
+// --- Bot Script Integrity ---
+//
+// The bot runs from a copy in the user data dir, which anything running as the user
+// can write to. Before every spawn, each script is checked against the SHA-256
+// manifest build.rs embedded from the bundled resources. A modified or missing file
+// is restored from the bundle; if that isn't possible the bot is not started.
+//
+// node_modules is too large to hash file by file at build time, so build.rs pins the
+// bundled node_modules.tar.gz instead. The first spawn of a session extracts a fresh
+// tree from that verified tarball and keeps a manifest of it in memory; later spawns
+// compare the tree against it and extract again on any difference. The manifest is
+// never written to disk, where it could be edited along with the files it vouches for.
+
+include!(concat!(env!("OUT_DIR"), "/bot_manifest.rs"));
+
+/// Manifest files in `dir` that are missing or don't match their hash.
+fn mismatches(dir: &Path) -> Vec<&'static str> {
+    BOT_MANIFEST
+        .iter()
+        .filter(|(name, hash)| {
+            std::fs::read(dir.join(name)).map(|bytes| crate::sha256_hex(&bytes) != *hash).unwrap_or(true)
+        })
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Copy `files` from the bundled resources, which must themselves match the manifest.
+fn restore(resource_dir: &Path, runtime_dir: &Path, files: &[&str]) -> Result<(), String> {
+    let bad_source = mismatches(resource_dir);
+    if let Some(name) = files.iter().find(|f| bad_source.contains(f)) {
+        return Err(format!("Bundled {} does not match this build", name));
+    }
+    for name in files {
+        let contents = std::fs::read(resource_dir.join(name)).map_err(|e| format!("Failed to read bundled {}: {}", name, e))?;
+        crate::fsutil::write_file(&runtime_dir.join(name), contents, crate::fsutil::WriteOptions::PLAIN)?;
+    }
+    Ok(())
+}
+
+/// Make sure the scripts in `runtime_dir` are exactly the ones this build shipped,
+/// restoring any that aren't from `resource_dir`.
+pub fn ensure_intact(runtime_dir: &Path, resource_dir: Option<&Path>) -> Result<(), String> {
+    let bad = mismatches(runtime_dir);
+    if bad.is_empty() {
+        return Ok(());
+    }
+    log::warn!("Bot scripts failed integrity check: {}", bad.join(", "));
+
+    let restored = match resource_dir {
+        Some(src) => restore(src, runtime_dir, &bad),
+        None => Err("bundled copies not found".into()),
+    };
+    let problem = match restored {
+        Err(e) => Some(e),
+        Ok(()) => mismatches(runtime_dir).first().map(|name| format!("{} still differs", name)),
+    };
+    if let Some(e) = problem {
+        return Err(format!(
+            "Refusing to start: bot scripts were modified ({}) and could not be restored: {}. Reinstall the app.",
+            bad.join(", "),
+            e
+        ));
+    }
+    log::info!("Restored bot scripts from the app bundle: {}", bad.join(", "));
+    Ok(())
+}
+
+const MODULES_TARBALL: &str = "node_modules.tar.gz";
+const MODULES_DIR: &str = "node_modules";
+
+/// Manifest of the node_modules tree extracted this session.
+static MODULES_TREE: Mutex<Option<Vec<(String, String)>>> = Mutex::new(None);
+
+/// Relative path and SHA-256 of every file under `dir`, sorted. Symlinks are recorded
+/// by target rather than followed.
+fn tree_manifest(dir: &Path) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let rel = path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let Ok(kind) = entry.file_type() else { continue };
+            let digest = if kind.is_symlink() {
+                let target = std::fs::read_link(&path).map(|t| t.to_string_lossy().to_string()).unwrap_or_default();
+                format!("link:{}", target)
+            } else if kind.is_dir() {
+                pending.push(path);
+                continue;
+            } else {
+                std::fs::read(&path).map(|bytes| crate::sha256_hex(&bytes)).unwrap_or_default()
+            };
+            out.push((rel, digest));
+        }
+    }
+    out.sort();
+    out
+}
+
+/// Replace `runtime_dir/node_modules` with a fresh copy from the bundled tarball,
+/// which must match the hash this build embedded.
+fn extract_modules(resource_dir: &Path, runtime_dir: &Path, expected: &str) -> Result<(), String> {
+    let tarball = resource_dir.join(MODULES_TARBALL);
+    let bytes = std::fs::read(&tarball).map_err(|e| format!("Failed to read bundled {}: {}", MODULES_TARBALL, e))?;
+    if crate::sha256_hex(&bytes) != expected {
+        return Err(format!("Bundled {} does not match this build", MODULES_TARBALL));
+    }
+    let modules = runtime_dir.join(MODULES_DIR);
+    if modules.exists() {
+        std::fs::remove_dir_all(&modules).map_err(|e| format!("Failed to remove {}: {}", modules.display(), e))?;
+    }
+    let output = StdCommand::new("tar")
+        .args(["xzf", &tarball.to_string_lossy()])
+        .current_dir(runtime_dir)
+        .output()
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to extract {}: {}", MODULES_TARBALL, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Make sure `runtime_dir/node_modules` is exactly what this build shipped. Builds
+/// without a bundled tarball (dev builds) have nothing to check against.
+pub fn ensure_modules_intact(runtime_dir: &Path, resource_dir: Option<&Path>) -> Result<(), String> {
+    let Some(expected) = BOT_MODULES_SHA256 else { return Ok(()) };
+    let mut known = MODULES_TREE.lock().unwrap();
+    let modules = runtime_dir.join(MODULES_DIR);
+    match known.as_ref() {
+        Some(tree) if *tree == tree_manifest(&modules) => return Ok(()),
+        Some(_) => log::warn!("Bot node_modules changed since it was extracted; extracting it again"),
+        None => {}
+    }
+
+    let extracted = match resource_dir {
+        Some(src) => extract_modules(src, runtime_dir, expected),
+        None => Err("bundled copies not found".into()),
+    };
+    if let Err(e) = extracted {
+        *known = None;
+        return Err(format!("Refusing to start: bot dependencies could not be verified: {}. Reinstall the app.", e));
+    }
+    *known = Some(tree_manifest(&modules));
+    Ok(())
+}
@@ -0,0 +1,486 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use zeroize::Zeroizing;
+use crate::audit_log;
+use crate::config::CONFIG_FILE;
+use crate::config_history::{self, ConfigSource};
+use crate::dotenv::{self, EnvFile, ENV_FILE};
+use crate::fsutil::{self, WriteOptions};
+use crate::security::expand_home;
+
+// --- Encrypted Key Store ---
+//
+// The Hyperliquid private key at rest, sealed with a passphrase: scrypt stretches the
+// passphrase into a 256-bit key and ChaCha20-Poly1305 encrypts. The header (format,
+// version, KDF parameters, salt, cipher) is the AEAD's associated data, so editing it
+// to weaken the KDF fails authentication. The decrypted key lives only in memory,
+// from unlock until lock or app exit.
+
+pub const KEYSTORE_FILE: &str = "keystore.json";
+const PLAINTEXT_KEY_FILE: &str = "private_key";
+const FORMAT: &str = "hl-signalbot-keystore";
+const VERSION: u32 = 1;
+const KDF: &str = "scrypt";
+const CIPHER: &str = "chacha20poly1305";
+/// 2^17 × r=8 uses 128 MiB and takes around a second, per scrypt's recommendation.
+const LOG_N: u8 = scrypt::Params::RECOMMENDED_LOG_N;
+const R: u32 = scrypt::Params::RECOMMENDED_R;
+const P: u32 = scrypt::Params::RECOMMENDED_P;
+/// Parameters accepted when reading. scrypt runs before the AEAD can reject a tampered
+/// header, so `r` and `p` must be exactly ours and `log_n` stays within 16 MiB–1 GiB.
+const LOG_N_RANGE: std::ops::RangeInclusive<u8> = 14..=20;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// The decrypted key, once unlocked.
+#[derive(Default)]
+pub struct Keystore {
+    key: Mutex<Option<Zeroizing<String>>>,
+}
+
+impl Keystore {
+    fn set(&self, key: Option<Zeroizing<String>>) {
+        *self.key.lock().unwrap() = key;
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.key.lock().unwrap().is_some()
+    }
+
+    pub fn key(&self) -> Option<Zeroizing<String>> {
+        self.key.lock().unwrap().clone()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeystoreFile {
+    format: String,
+    version: u32,
+    kdf: String,
+    kdf_params: KdfParams,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+    created_at: String,
+}
+
+impl KeystoreFile {
+    fn associated_data(&self) -> String {
+        let k = &self.kdf_params;
+        format!("{}/v{}/{}/{}/{}/{}/{}/{}", self.format, self.version, self.kdf, k.log_n, k.r, k.p, k.salt, self.cipher)
+    }
+
+    /// Sealed with an older version or weaker parameters than new key stores get.
+    fn outdated(&self) -> bool {
+        let k = &self.kdf_params;
+        self.version < VERSION || k.log_n < LOG_N
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreStatus {
+    pub exists: bool,
+    pub unlocked: bool,
+    pub version: Option<u32>,
+    /// Re-encrypting would move it to the current format and parameters.
+    pub outdated: bool,
+    /// A plaintext copy of the key is still on disk (private_key or .env).
+    pub plaintext_key: bool,
+}
+
+fn keystore_path(dir: &Path) -> PathBuf {
+    dir.join(KEYSTORE_FILE)
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, params: &KdfParams) -> Result<Zeroizing<[u8; 32]>, String> {
+    let salt = hex::decode(&params.salt).map_err(|_| "Key store salt is corrupted".to_string())?;
+    let scrypt_params =
+        scrypt::Params::new(params.log_n, params.r, params.p, 32).map_err(|e| format!("Invalid key store parameters: {}", e))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(passphrase.as_bytes(), &salt, &scrypt_params, key.as_mut()).map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+/// Encrypt `private_key` under `passphrase` with a fresh salt and nonce.
+fn seal(private_key: &str, passphrase: &str) -> Result<KeystoreFile, String> {
+    seal_with(private_key, passphrase, LOG_N)
+}
+
+fn seal_with(private_key: &str, passphrase: &str, log_n: u8) -> Result<KeystoreFile, String> {
+    let mut file = KeystoreFile {
+        format: FORMAT.into(),
+        version: VERSION,
+        kdf: KDF.into(),
+        kdf_params: KdfParams { log_n, r: R, p: P, salt: hex::encode(random_bytes::<SALT_LEN>()) },
+        cipher: CIPHER.into(),
+        nonce: hex::encode(random_bytes::<NONCE_LEN>()),
+        ciphertext: String::new(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let key = derive_key(passphrase, &file.kdf_params)?;
+    let nonce = hex::decode(&file.nonce).map_err(|e| e.to_string())?;
+    let aad = file.associated_data();
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: private_key.as_bytes(), aad: aad.as_bytes() })
+        .map_err(|_| "Encryption failed".to_string())?;
+    file.ciphertext = hex::encode(ciphertext);
+    Ok(file)
+}
+
+fn open(file: &KeystoreFile, passphrase: &str) -> Result<Zeroizing<String>, String> {
+    if file.format != FORMAT || file.kdf != KDF || file.cipher != CIPHER {
+        return Err("Not a key store this app can read".into());
+    }
+    if file.version > VERSION {
+        return Err(format!("Key store version {} needs a newer version of the app", file.version));
+    }
+    let k = &file.kdf_params;
+    if !LOG_N_RANGE.contains(&k.log_n) || k.r != R || k.p != P {
+        return Err("Key store parameters are out of range".into());
+    }
+    if hex::decode(&k.salt).map_or(true, |salt| salt.len() != SALT_LEN) {
+        return Err("Key store salt is corrupted".into());
+    }
+    let key = derive_key(passphrase, &file.kdf_params)?;
+    let nonce = hex::decode(&file.nonce).ok().filter(|n| n.len() == NONCE_LEN).ok_or("Key store nonce is corrupted")?;
+    let ciphertext = hex::decode(&file.ciphertext).map_err(|_| "Key store ciphertext is corrupted".to_string())?;
+    let aad = file.associated_data();
+    let plain = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: aad.as_bytes() })
+        .map(Zeroizing::new)
+        .map_err(|_| "Wrong passphrase, or the key store has been modified".to_string())?;
+    String::from_utf8(plain.to_vec()).map(Zeroizing::new).map_err(|_| "Key store contents are corrupted".into())
+}
+
+fn load(dir: &Path) -> Result<Option<KeystoreFile>, String> {
+    let path = keystore_path(dir);
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).map(Some).map_err(|e| format!("{} is not a valid key store: {}", KEYSTORE_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// No `.bak`: after a passphrase change the old file must not linger under the old one.
+fn save(dir: &Path, file: &KeystoreFile) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    fsutil::write_file(&keystore_path(dir), text, WriteOptions { secret: true, backup: false })
+}
+
+/// Key files named by `HL_PRIVATE_KEY_PATH` in .env or `wallet.privateKeyPath` in
+/// config.json. These often sit outside the data dir.
+fn configured_key_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<String> = Vec::new();
+    if let Ok(env) = EnvFile::load(&dir.join(ENV_FILE)) {
+        paths.extend(env.get("HL_PRIVATE_KEY_PATH").map(str::to_string));
+    }
+    if let Some(cfg) = std::fs::read_to_string(dir.join(CONFIG_FILE)).ok().and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok()) {
+        paths.extend(cfg["wallet"]["privateKeyPath"].as_str().map(str::to_string));
+    }
+    let mut out: Vec<PathBuf> = paths.iter().filter(|p| !p.trim().is_empty()).map(|p| expand_home(p.trim())).collect();
+    out.dedup();
+    out
+}
+
+fn read_key_file(path: &Path) -> Option<Zeroizing<String>> {
+    let raw = std::fs::read_to_string(path).ok().map(Zeroizing::new)?;
+    Some(Zeroizing::new(raw.trim().to_string())).filter(|k| !k.is_empty())
+}
+
+/// A plaintext key left on disk: the data dir key file, .env, or a configured key file.
+fn plaintext_key(dir: &Path) -> Option<Zeroizing<String>> {
+    read_key_file(&dir.join(PLAINTEXT_KEY_FILE))
+        .or_else(|| {
+            let env = EnvFile::load(&dir.join(ENV_FILE)).ok()?;
+            env.get("HL_PRIVATE_KEY").filter(|k| !k.is_empty()).map(|k| Zeroizing::new(k.to_string()))
+        })
+        .or_else(|| configured_key_paths(dir).iter().find_map(|p| read_key_file(p)))
+}
+
+/// Delete the plaintext copies of `key` the key store replaces, including their
+/// backups, and the settings that point at them. A configured key file is only deleted
+/// when it holds this very key; otherwise it is left in place with a warning.
+fn remove_plaintext(dir: &Path, key: &str) -> Result<(), String> {
+    let key_file = dir.join(PLAINTEXT_KEY_FILE);
+    for path in [fsutil::backup_path(&key_file), key_file] {
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+    for path in configured_key_paths(dir) {
+        match read_key_file(&path) {
+            Some(k) if k.as_str() == key => {
+                std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+                log::info!("Removed plaintext key file {}", path.display());
+            }
+            Some(_) => log::warn!("{} holds a different key than the key store; left in place", path.display()),
+            None => {}
+        }
+    }
+
+    let cfg_path = dir.join(CONFIG_FILE);
+    if let Some(mut cfg) = std::fs::read_to_string(&cfg_path).ok().and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok()) {
+        if let Some(wallet) = cfg.get_mut("wallet").and_then(|w| w.as_object_mut()) {
+            if wallet.remove("privateKeyPath").is_some() {
+                let text = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+                config_history::write_config_file(dir, &text, ConfigSource::Keystore)?;
+            }
+        }
+    }
+
+    let env_path = dir.join(ENV_FILE);
+    let env = EnvFile::load(&env_path)?;
+    if env.get("HL_PRIVATE_KEY").is_some() || env.get("HL_PRIVATE_KEY_PATH").is_some() {
+        dotenv::update(&env_path, &Default::default(), &["HL_PRIVATE_KEY".into(), "HL_PRIVATE_KEY_PATH".into()])?;
+        let _ = std::fs::remove_file(fsutil::backup_path(&env_path));
+    }
+    Ok(())
+}
+
+/// The key to hand a bot process: `None` without a key store, where the bot finds its
+/// key in .env or config as before, and an error while the key store is locked.
+pub fn key_for_launch(app: &tauri::AppHandle, dir: &Path) -> Result<Option<Zeroizing<String>>, String> {
+    if !keystore_path(dir).exists() {
+        return Ok(None);
+    }
+    match app.state::<Keystore>().key() {
+        Some(key) => Ok(Some(key)),
+        None => Err("The key store is locked. Unlock it with your passphrase to start the bot.".into()),
+    }
+}
+
+// --- Commands ---
+//
+// scrypt takes about a second, so these are async to keep it off the main thread.
+
+#[tauri::command]
+pub async fn keystore_status(app: tauri::AppHandle) -> Result<KeystoreStatus, String> {
+    let dir = crate::bot_config_dir(&app)?;
+    let file = load(&dir)?;
+    Ok(KeystoreStatus {
+        exists: file.is_some(),
+        unlocked: app.state::<Keystore>().is_unlocked(),
+        version: file.as_ref().map(|f| f.version),
+        outdated: file.as_ref().is_some_and(KeystoreFile::outdated),
+        plaintext_key: plaintext_key(&dir).is_some(),
+    })
+}
+
+/// Encrypt `private_key`, or the plaintext key already on disk when it's omitted, then
+/// remove the plaintext copies. The new key store starts unlocked. An existing key
+/// store is only replaced when `overwrite` is set, as the setup wizard does.
+#[tauri::command]
+pub async fn create_keystore(
+    app: tauri::AppHandle,
+    private_key: Option<String>,
+    passphrase: String,
+    overwrite: Option<bool>,
+) -> Result<(), String> {
+    let params = serde_json::json!({ "privateKeyProvided": private_key.is_some(), "overwrite": overwrite });
+    let result = create(&app, private_key, &passphrase, overwrite.unwrap_or(false));
+    audit_log::record(&app, "create_keystore", params, result)
+}
+
+fn create(app: &tauri::AppHandle, private_key: Option<String>, passphrase: &str, overwrite: bool) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    if keystore_path(&dir).exists() && !overwrite {
+        return Err("A key store already exists. Change its passphrase instead.".into());
+    }
+    check_passphrase(passphrase)?;
+    let key = match private_key.map(Zeroizing::new) {
+        Some(k) => Zeroizing::new(k.trim().to_string()),
+        None => plaintext_key(&dir).ok_or("No private key found to encrypt")?,
+    };
+    crate::wallet::parse_private_key(&key)?;
+
+    save(&dir, &seal(&key, passphrase)?)?;
+    remove_plaintext(&dir, &key)?;
+    app.state::<Keystore>().set(Some(key));
+    log::info!("Created encrypted key store");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unlock_keystore(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    let result = unlock(&app, &passphrase);
+    audit_log::record(&app, "unlock_keystore", serde_json::json!({}), result)
+}
+
+fn unlock(app: &tauri::AppHandle, passphrase: &str) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    let file = load(&dir)?.ok_or("No key store found. Set one up in Settings.")?;
+    let key = open(&file, passphrase)?;
+    crate::wallet::parse_private_key(&key)?;
+    app.state::<Keystore>().set(Some(key));
+    Ok(())
+}
+
+/// Forget the decrypted key. A running bot keeps the key it was started with.
+#[tauri::command]
+pub fn lock_keystore(app: tauri::AppHandle) {
+    app.state::<Keystore>().set(None);
+    let _ = audit_log::record(&app, "lock_keystore", serde_json::json!({}), Ok::<(), String>(()));
+}
+
+/// Delete the key store and forget the decrypted key, for going back to a key in .env.
+/// While a key store exists the bot is handed its key, whatever .env says.
+#[tauri::command]
+pub fn delete_keystore(app: tauri::AppHandle) -> Result<(), String> {
+    let result = delete(&app);
+    audit_log::record(&app, "delete_keystore", serde_json::json!({}), result)
+}
+
+fn delete(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    app.state::<Keystore>().set(None);
+    let path = keystore_path(&dir);
+    let existed = path.exists();
+    for p in [fsutil::backup_path(&path), path] {
+        if p.exists() {
+            std::fs::remove_file(&p).map_err(|e| format!("Failed to remove {}: {}", p.display(), e))?;
+        }
+    }
+    if existed {
+        log::info!("Deleted encrypted key store");
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn change_keystore_passphrase(app: tauri::AppHandle, current: String, new: String) -> Result<(), String> {
+    let result = change_passphrase(&app, &current, &new);
+    audit_log::record(&app, "change_keystore_passphrase", serde_json::json!({}), result)
+}
+
+fn change_passphrase(app: &tauri::AppHandle, current: &str, new: &str) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    let file = load(&dir)?.ok_or("No key store found")?;
+    check_passphrase(new)?;
+    let key = open(&file, current)?;
+    save(&dir, &seal(&key, new)?)?;
+    log::info!("Changed key store passphrase");
+    Ok(())
+}
+
+/// Re-seal under the same passphrase with a fresh salt and nonce, at the current
+/// version and KDF parameters.
+#[tauri::command]
+pub async fn reencrypt_keystore(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    let result = reencrypt(&app, &passphrase);
+    audit_log::record(&app, "reencrypt_keystore", serde_json::json!({}), result)
+}
+
+fn reencrypt(app: &tauri::AppHandle, passphrase: &str) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    let file = load(&dir)?.ok_or("No key store found")?;
+    let key = open(&file, passphrase)?;
+    save(&dir, &seal(&key, passphrase)?)?;
+    log::info!("Re-encrypted key store (was v{}, log_n {})", file.version, file.kdf_params.log_n);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const PASSPHRASE: &str = "correct horse battery";
+
+    /// Sealed with the cheapest parameters `open` accepts, to keep the tests fast.
+    fn sealed() -> KeystoreFile {
+        seal_with(KEY, PASSPHRASE, *LOG_N_RANGE.start()).unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let file = sealed();
+        assert_eq!(open(&file, PASSPHRASE).unwrap().as_str(), KEY);
+        let reread: KeystoreFile = serde_json::from_str(&serde_json::to_string(&file).unwrap()).unwrap();
+        assert_eq!(open(&reread, PASSPHRASE).unwrap().as_str(), KEY);
+    }
+
+    #[test]
+    fn rejects_a_wrong_passphrase() {
+        let err = open(&sealed(), "wrong horse battery").unwrap_err();
+        assert_eq!(err, "Wrong passphrase, or the key store has been modified");
+    }
+
+    #[test]
+    fn edited_header_fails_authentication() {
+        let original = sealed();
+
+        let mut file = original.clone();
+        file.kdf_params.log_n += 1;
+        assert_eq!(open(&file, PASSPHRASE).unwrap_err(), "Wrong passphrase, or the key store has been modified");
+
+        let mut file = original.clone();
+        file.version = 0;
+        assert_eq!(open(&file, PASSPHRASE).unwrap_err(), "Wrong passphrase, or the key store has been modified");
+
+        let mut file = original;
+        file.created_at = "2000-01-01T00:00:00Z".into();
+        assert_eq!(open(&file, PASSPHRASE).unwrap().as_str(), KEY, "created_at is not part of the header");
+    }
+
+    #[test]
+    fn rejects_out_of_range_parameters_before_deriving() {
+        let original = sealed();
+        let edits: [fn(&mut KdfParams); 5] = [
+            |k| k.log_n = *LOG_N_RANGE.start() - 1,
+            |k| k.log_n = *LOG_N_RANGE.end() + 1,
+            |k| k.r = 8192,
+            |k| k.p = 1 << 20,
+            |k| k.r = R - 1,
+        ];
+        for edit in edits {
+            let mut file = original.clone();
+            edit(&mut file.kdf_params);
+            assert_eq!(open(&file, PASSPHRASE).unwrap_err(), "Key store parameters are out of range");
+        }
+
+        let mut file = original;
+        file.kdf_params.salt = "00".into();
+        assert_eq!(open(&file, PASSPHRASE).unwrap_err(), "Key store salt is corrupted");
+    }
+
+    #[test]
+    fn rejects_other_formats_and_newer_versions() {
+        let mut file = sealed();
+        file.cipher = "aes-256-gcm".into();
+        assert_eq!(open(&file, PASSPHRASE).unwrap_err(), "Not a key store this app can read");
+
+        let mut file = sealed();
+        file.version = VERSION + 1;
+        assert!(open(&file, PASSPHRASE).unwrap_err().contains("needs a newer version"));
+    }
+}
@@ -1,8 +1,31 @@
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 use std::process::{Command as StdCommand, Stdio, Child};
 use std::sync::Mutex;
 use std::time::Instant;
 use tauri::{Emitter, Manager, State};
+use zeroize::Zeroizing;
+
+mod analytics;
+mod audit_log;
+mod bundle;
+mod config;
+mod config_history;
+mod config_watch;
+mod db;
+mod dotenv;
+mod equity;
+mod file_access;
+mod fsutil;
+mod integrity;
+mod keystore;
+mod license;
+mod profiles;
+mod redact;
+mod risk_sim;
+mod security;
+mod settings;
+mod trades;
+mod wallet;
 
 // --- Bot State ---
 
@@ -11,6 +34,8 @@ struct BotState {
     child: Mutex<Option<Child>>,
     last_heartbeat: Mutex<Option<Instant>>,
     last_error: Mutex<Option<String>>,
+    run_id: Mutex<Option<i64>>,
+    restart_pending: Mutex<bool>,
 }
 
 impl Default for BotState {
@@ -20,6 +45,8 @@ impl Default for BotState {
             child: Mutex::new(None),
             last_heartbeat: Mutex::new(None),
             last_error: Mutex::new(None),
+            run_id: Mutex::new(None),
+            restart_pending: Mutex::new(false),
         }
     }
 }
@@ -31,8 +58,9 @@ fn app_version(app: &tauri::AppHandle) -> String {
 }
 
 fn find_bot_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    // 1. Dev mode: project root bot/ with node_modules already installed
-    if let Ok(cwd) = std::env::current_dir() {
+    // 1. Dev mode: project root bot/ with node_modules already installed. Debug builds
+    // only, since these scripts aren't covered by the integrity check.
+    if let Some(cwd) = std::env::current_dir().ok().filter(|_| cfg!(debug_assertions)) {
         for base in &[cwd.clone(), cwd.join("..").canonicalize().unwrap_or(cwd.clone())] {
             let d = base.join("bot");
             if d.join("index.mjs").exists() && d.join("node_modules").exists() {
@@ -68,7 +96,8 @@ fn find_bot_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
                 let _ = std::fs::remove_dir_all(runtime_dir.join("node_modules"));
             }
             provision_bot_runtime(&resource_bot, &runtime_dir)?;
-            let _ = std::fs::write(&version_file, &current_version);
+            let _ = fsutil::write_file(&version_file, &current_version, fsutil::WriteOptions::PLAIN);
+            migrate_user_config();
         }
     } else if !has_runtime {
         return Err("Cannot locate bundled bot files. Reinstall the app.".into());
@@ -77,6 +106,37 @@ fn find_bot_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     Ok(runtime_dir)
 }
 
+/// `find_bot_dir`, with the provisioned runtime copy checked against the script
+/// manifest and node_modules tarball of this build. Call before every spawn.
+fn verified_bot_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let bot_dir = find_bot_dir(app)?;
+    if bot_dir == user_data_dir()?.join("bot") {
+        let resource_dir = find_resource_bot_dir(app).ok();
+        integrity::ensure_intact(&bot_dir, resource_dir.as_deref())?;
+        integrity::ensure_modules_intact(&bot_dir, resource_dir.as_deref())?;
+    }
+    Ok(bot_dir)
+}
+
+/// Bring config.json up to the current schema after an app upgrade.
+fn migrate_user_config() {
+    let dir = match user_data_dir() {
+        Ok(d) => d,
+        Err(e) => return log::warn!("Config migration skipped: {}", e),
+    };
+    match config::migrate_config_file(&dir) {
+        Ok(Some(report)) => log::info!(
+            "Migrated config.json from schema v{} to v{} (backup: {:?}): {}",
+            report.from,
+            report.to,
+            report.backup,
+            report.changes.join(", ")
+        ),
+        Ok(None) => {}
+        Err(e) => log::warn!("Config migration failed: {}", e),
+    }
+}
+
 fn find_resource_bot_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     if let Ok(exe) = std::env::current_exe() {
         if let Some(dir) = exe.parent() {
@@ -215,7 +275,12 @@ fn user_data_dir() -> Result<std::path::PathBuf, String> {
     let base = dirs::config_dir().ok_or("Cannot determine config directory")?;
     let d = base.join("hl-signalbot");
     if !d.exists() {
-        std::fs::create_dir_all(&d).map_err(|e| format!("Cannot create {}: {}", d.display(), e))?;
+        // Owner-only from the start; it holds the key store and .env
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(&d).map_err(|e| format!("Cannot create {}: {}", d.display(), e))?;
     }
     Ok(d)
 }
@@ -351,10 +416,10 @@ fn find_node() -> Result<String, String> {
 
     // Validate each candidate by running `node --version`
     for path in &candidates {
-        if std::path::Path::new(path).exists() {
-            if StdCommand::new(path).arg("--version").stdout(Stdio::null()).stderr(Stdio::null()).status().is_ok() {
-                return Ok(path.clone());
-            }
+        if std::path::Path::new(path).exists()
+            && StdCommand::new(path).arg("--version").stdout(Stdio::null()).stderr(Stdio::null()).status().is_ok()
+        {
+            return Ok(path.clone());
         }
     }
 
@@ -397,50 +462,65 @@ fn find_node() -> Result<String, String> {
     Err("Node.js is not installed. Download it from https://nodejs.org (LTS version).".into())
 }
 
-// --- Tauri Commands ---
+// --- Run Fingerprint ---
 
-#[tauri::command]
-fn is_bot_running(state: State<BotState>) -> bool {
-    *state.running.lock().unwrap()
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
 }
 
-#[tauri::command]
-async fn validate_license(key: String) -> Result<bool, String> {
-    let key = key.trim().to_uppercase();
-    if key.is_empty() {
-        return Ok(false);
+/// Compact JSON with object keys sorted at every level, independent of whether
+/// serde_json preserves insertion order.
+pub(crate) fn canonical_json(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|k| format!("{}:{}", serde_json::Value::String(k.clone()), canonical_json(&map[k])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(","))
+        }
+        other => other.to_string(),
     }
+}
 
-    // Try online validation first
-    let api_url = option_env!("LICENSE_API_URL").unwrap_or("https://c13studios.com");
-    let url = format!("{}/api/validate", api_url);
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .unwrap_or_default();
-
-    match client
-        .post(&url)
-        .json(&serde_json::json!({ "key": &key }))
-        .send()
-        .await
-    {
-        Ok(resp) => {
-            if let Ok(body) = resp.json::<serde_json::Value>().await {
-                return Ok(body["valid"].as_bool().unwrap_or(false));
-            }
-        }
-        Err(_) => {}
+/// Versions and config a run is launched with. The config is hashed in canonical
+/// form (sorted keys, no whitespace) so reformatting the file keeps the same hash.
+fn run_info(app: &tauri::AppHandle, node: &str, bot_dir: &std::path::Path, cfg_path: &std::path::Path) -> db::RunInfo {
+    let node_version = StdCommand::new(node)
+        .arg("--version")
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    let bot_version = std::fs::read_to_string(bot_dir.join(".bot-version"))
+        .ok()
+        .map(|v| v.trim().to_string());
+    let config = std::fs::read_to_string(cfg_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .map(|v| canonical_json(&v));
+
+    db::RunInfo {
+        app_version: app_version(app),
+        bot_version,
+        node_version,
+        config_hash: config.as_deref().map(|c| sha256_hex(c.as_bytes())),
+        config_snapshot: config,
     }
+}
 
-    // Offline fallback: accept properly formatted keys (SB-XXXX-XXXX-XXXX-XXXX)
-    let valid_format = key.starts_with("SB-")
-        && key.len() == 22
-        && key.split('-').count() == 5
-        && key.split('-').skip(1).all(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_alphanumeric()));
+// --- Tauri Commands ---
 
-    Ok(valid_format)
+#[tauri::command]
+fn is_bot_running(state: State<BotState>) -> bool {
+    *state.running.lock().unwrap()
 }
 
 #[tauri::command]
@@ -459,48 +539,90 @@ fn get_config_dir(app: tauri::AppHandle) -> Result<String, String> {
 }
 
 #[tauri::command]
-fn write_bot_file(app: tauri::AppHandle, filename: String, contents: String) -> Result<(), String> {
-    let dir = bot_config_dir(&app)?;
-    let path = dir.join(&filename);
-    std::fs::write(&path, &contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+fn write_bot_file(app: tauri::AppHandle, filename: String, contents: String) -> Result<(), file_access::FileAccessError> {
+    let params = serde_json::json!({
+        "filename": &filename,
+        "bytes": contents.len(),
+        "fingerprint": audit_log::fingerprint(contents.as_bytes()),
+    });
+    let result = write_data_file(&app, &filename, &contents);
+    audit_log::record(&app, "write_bot_file", params, result)
+}
 
-    // Restrict permissions on sensitive files
-    #[cfg(unix)]
-    if filename.contains("private") || filename == ".env" {
-        use std::os::unix::fs::PermissionsExt;
-        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+fn write_data_file(app: &tauri::AppHandle, filename: &str, contents: &str) -> Result<(), file_access::FileAccessError> {
+    let target = file_access::resolve(filename, file_access::Access::Write)?;
+    if target.file == file_access::LogicalFile::Config {
+        let cfg = config::parse_valid(contents)?;
+        let text = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+        let dir = target.path.parent().unwrap_or(std::path::Path::new("."));
+        config_history::write_config_file(dir, &text, config_history::ConfigSource::Ui)?;
+        config_watch::apply_reload_policy(app, dir, &text);
+        return Ok(());
     }
-    Ok(())
+
+    // Restrict permissions on sensitive files
+    let opts = if target.secret { fsutil::WriteOptions::SECRET } else { fsutil::WriteOptions::BACKUP };
+    Ok(fsutil::write_file(&target.path, contents, opts)?)
 }
 
 #[tauri::command]
-fn read_bot_file(app: tauri::AppHandle, filename: String) -> Result<String, String> {
-    let dir = bot_config_dir(&app)?;
-    let path = dir.join(&filename);
-    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+fn read_bot_file(filename: String) -> Result<String, file_access::FileAccessError> {
+    let target = file_access::resolve(&filename, file_access::Access::Read)?;
+    std::fs::read_to_string(&target.path)
+        .map_err(|e| file_access::FileAccessError::Io(format!("Failed to read {}: {}", target.path.display(), e)))
 }
 
 #[tauri::command]
-fn bot_file_exists(app: tauri::AppHandle, filename: String) -> bool {
-    bot_config_dir(&app)
-        .map(|dir| dir.join(&filename).exists())
+fn bot_file_exists(filename: String) -> bool {
+    file_access::resolve(&filename, file_access::Access::Exists)
+        .map(|target| target.path.exists())
         .unwrap_or(false)
 }
 
-/// Write a secret file with restrictive permissions (600 on Unix).
+/// Write a secret file with restrictive permissions (600 on Unix). Only the declared
+/// secret files in the data folder can be written this way.
 #[tauri::command]
-fn write_secret_file(path: String, contents: String) -> Result<(), String> {
-    let p = std::path::Path::new(&path);
-    if let Some(parent) = p.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| format!("mkdir failed: {}", e))?;
+fn write_secret_file(app: tauri::AppHandle, path: String, contents: String) -> Result<(), file_access::FileAccessError> {
+    let params = serde_json::json!({ "path": &path, "contents": audit_log::REDACTED });
+    let result = write_secret(&path, &contents);
+    audit_log::record(&app, "write_secret_file", params, result)
+}
+
+fn write_secret(path: &str, contents: &str) -> Result<(), file_access::FileAccessError> {
+    let target = file_access::resolve_path(path, file_access::Access::Write)?;
+    if !target.secret {
+        return Err(file_access::FileAccessError::Denied { name: path.to_string(), access: file_access::Access::Write });
     }
-    std::fs::write(&p, &contents).map_err(|e| format!("write failed: {}", e))?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let _ = std::fs::set_permissions(&p, std::fs::Permissions::from_mode(0o600));
+    Ok(fsutil::write_file(&target.path, contents, fsutil::WriteOptions::SECRET)?)
+}
+
+/// Send a test message to `chat` with `token`, or with the saved tg_token when none is
+/// given, so the saved token never has to come back to the UI.
+#[tauri::command]
+async fn send_telegram_test(app: tauri::AppHandle, chat: String, token: Option<String>) -> Result<(), String> {
+    let token = match token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        Some(t) => t,
+        None => std::fs::read_to_string(bot_config_dir(&app)?.join("tg_token"))
+            .map(|t| t.trim().to_string())
+            .map_err(|_| "No bot token saved yet".to_string())?,
+    };
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    // `without_url`: the token is part of the URL
+    let resp = client
+        .post(format!("https://api.telegram.org/bot{}/sendMessage", token))
+        .json(&serde_json::json!({ "chat_id": chat.trim(), "text": "HL Signalbot: test ping", "disable_web_page_preview": true }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed: {}", e.without_url()))?;
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("Failed: {}", e.without_url()))?;
+    if body["ok"].as_bool() == Some(true) {
+        Ok(())
+    } else {
+        Err(format!("Error: {}", body["description"].as_str().unwrap_or("unknown")))
     }
-    Ok(())
 }
 
 #[tauri::command]
@@ -513,14 +635,20 @@ fn get_health(state: State<BotState>) -> (bool, Option<u64>, Option<String>) {
 
 #[tauri::command]
 fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String> {
+    let result = launch_bot(app.clone(), state);
+    audit_log::record(&app, "start_bot", serde_json::json!({}), result)
+}
+
+fn launch_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String> {
     let mut running = state.running.lock().unwrap();
     if *running {
         return Err("Bot is already running".into());
     }
 
     let node = find_node()?;
-    let bot_dir = find_bot_dir(&app)?;
+    let bot_dir = verified_bot_dir(&app)?;
     let config_dir = bot_config_dir(&app)?;
+    license::ensure_bot_allowed(&config_dir)?;
 
     let cli = bot_dir.join("cli.mjs");
     if !cli.exists() {
@@ -532,6 +660,8 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
     if !cfg_path.exists() {
         return Err("config.json not found. Complete setup first.".into());
     }
+    check_config_file(&cfg_path)?;
+    let key = keystore::key_for_launch(&app, &config_dir)?;
 
     let mut cmd = StdCommand::new(&node);
     cmd.arg(cli.to_str().unwrap())
@@ -547,6 +677,7 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
         cmd.env("DOTENV_CONFIG_PATH", env_path.to_str().unwrap());
     }
     cmd.env("DATA_DIR", config_dir.to_str().unwrap());
+    expect_key_on_stdin(&mut cmd, key.is_some());
 
     let mut child = cmd.spawn().map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
@@ -555,24 +686,44 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
             format!("Failed to start bot: {}", e)
         }
     })?;
+    if let Err(e) = send_key(&mut child, key) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(e);
+    }
 
     *running = true;
     *state.last_heartbeat.lock().unwrap() = Some(Instant::now());
     *state.last_error.lock().unwrap() = None;
+    let info = run_info(&app, &node, &bot_dir, &cfg_path);
+    let run_id = app.state::<db::Db>().begin_run(&info).map_err(|e| log::warn!("Failed to record run: {}", e)).ok();
+    *state.run_id.lock().unwrap() = run_id;
 
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
     *state.child.lock().unwrap() = Some(child);
     drop(running);
 
+    // Every line is redacted before it is emitted, stored or kept as last_error
+    let redactor = std::sync::Arc::new(redact::Redactor::load(&config_dir));
+
     // Stream stdout
     if let Some(out) = stdout {
         let h = app.clone();
+        let redactor = redactor.clone();
+        let mut equity = equity::EquityRecorder::new(&config_dir);
         std::thread::spawn(move || {
             let reader = BufReader::new(out);
-            for line in reader.lines().flatten() {
+            for line in reader.lines().map_while(Result::ok) {
+                let line = redactor.apply(&line).into_owned();
                 // Update heartbeat on any output
                 let _ = h.state::<BotState>().last_heartbeat.lock().map(|mut hb| *hb = Some(Instant::now()));
+                if line.starts_with('{') {
+                    if let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) {
+                        equity.observe(&event);
+                        h.state::<db::Db>().insert_event(run_id, &event);
+                    }
+                }
                 let _ = h.emit("bot-event", &line);
             }
         });
@@ -584,7 +735,8 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
         std::thread::spawn(move || {
             let reader = BufReader::new(err);
             let mut error_lines: Vec<String> = Vec::new();
-            for line in reader.lines().flatten() {
+            for line in reader.lines().map_while(Result::ok) {
+                let line = redactor.apply(&line).into_owned();
                 error_lines.push(line.clone());
                 let event = serde_json::json!({"type": "log", "message": line});
                 let _ = h.emit("bot-event", &event.to_string());
@@ -593,6 +745,7 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
                 let full = error_lines.join("\n");
                 let _ = h.state::<BotState>().last_error.lock().map(|mut e| *e = Some(full.clone()));
                 let event = serde_json::json!({"type": "error", "message": full});
+                h.state::<db::Db>().insert_event(run_id, &event);
                 let _ = h.emit("bot-event", &event.to_string());
             }
         });
@@ -621,12 +774,16 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
                         let _ = h2.emit("bot-event", &event.to_string());
                         *st.running.lock().unwrap() = false;
                         *child_lock = None;
+                        drop(child_lock);
+                        finish_run(&h2, status.code(), db::ExitKind::from_code(status.code()));
                         break;
                     }
                     Ok(None) => {}
                     Err(_) => {
                         *st.running.lock().unwrap() = false;
                         *child_lock = None;
+                        drop(child_lock);
+                        finish_run(&h2, None, db::ExitKind::Lost);
                         break;
                     }
                 }
@@ -639,8 +796,50 @@ fn start_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String
     Ok(())
 }
 
+/// Never let a private key reach a bot process through its environment, where
+/// /proc/<pid>/environ exposes it for the life of the process. With `from_stdin` the
+/// child reads the key from stdin instead (see `send_key`).
+fn expect_key_on_stdin(cmd: &mut StdCommand, from_stdin: bool) {
+    cmd.env_remove("HL_PRIVATE_KEY");
+    if from_stdin {
+        cmd.env("HL_KEY_FROM_STDIN", "1").stdin(Stdio::piped());
+    }
+}
+
+/// Write the key to the child's stdin once and close it. The key and the buffer are
+/// zeroized when they drop at the end of this function.
+fn send_key(child: &mut Child, key: Option<Zeroizing<String>>) -> Result<(), String> {
+    let Some(key) = key else { return Ok(()) };
+    let mut stdin = child.stdin.take().ok_or("Bot process has no stdin to pass the key")?;
+    let mut line = Zeroizing::new(Vec::with_capacity(key.len() + 1));
+    line.extend_from_slice(key.as_bytes());
+    line.push(b'\n');
+    stdin.write_all(&line).map_err(|e| format!("Failed to pass the key to the bot: {}", e))
+}
+
+/// Fail with the schema errors if config.json would not pass `write_config`.
+fn check_config_file(cfg_path: &std::path::Path) -> Result<(), String> {
+    let raw = std::fs::read_to_string(cfg_path).map_err(|e| format!("Failed to read {}: {}", cfg_path.display(), e))?;
+    config::parse_valid(&raw).map(|_| ())
+}
+
+/// Close out the supervisor run record for the bot process that just exited.
+fn finish_run(app: &tauri::AppHandle, exit_code: Option<i32>, kind: db::ExitKind) {
+    let run_id = app.state::<BotState>().run_id.lock().unwrap().take();
+    if let Some(id) = run_id {
+        if let Err(e) = app.state::<db::Db>().end_run(id, exit_code, kind) {
+            log::warn!("Failed to record end of run {}: {}", id, e);
+        }
+    }
+}
+
 #[tauri::command]
-fn stop_bot(state: State<BotState>) -> Result<(), String> {
+fn stop_bot(app: tauri::AppHandle, state: State<BotState>) -> Result<(), String> {
+    let result = terminate_bot(&app, state);
+    audit_log::record(&app, "stop_bot", serde_json::json!({}), result)
+}
+
+fn terminate_bot(app: &tauri::AppHandle, state: State<BotState>) -> Result<(), String> {
     let mut child_lock = state.child.lock().unwrap();
     if let Some(ref mut child) = *child_lock {
         // Graceful shutdown: SIGTERM first, then SIGKILL after timeout
@@ -652,18 +851,20 @@ fn stop_bot(state: State<BotState>) -> Result<(), String> {
             // Give it 3 seconds to shut down gracefully
             for _ in 0..30 {
                 std::thread::sleep(std::time::Duration::from_millis(100));
-                if let Ok(Some(_)) = child.try_wait() {
+                if let Ok(Some(status)) = child.try_wait() {
                     *state.running.lock().unwrap() = false;
                     *child_lock = None;
+                    finish_run(app, status.code(), db::ExitKind::Stopped);
                     return Ok(());
                 }
             }
         }
         // Force kill if still running
         let _ = child.kill();
-        let _ = child.wait();
+        let code = child.wait().ok().and_then(|s| s.code());
         *state.running.lock().unwrap() = false;
         *child_lock = None;
+        finish_run(app, code, db::ExitKind::Stopped);
         Ok(())
     } else {
         *state.running.lock().unwrap() = false;
@@ -673,7 +874,7 @@ fn stop_bot(state: State<BotState>) -> Result<(), String> {
 
 fn run_close_script(app: &tauri::AppHandle, check_only: bool) -> Result<String, String> {
     let node = find_node()?;
-    let bot_dir = find_bot_dir(app)?;
+    let bot_dir = verified_bot_dir(app)?;
     let config_dir = bot_config_dir(app)?;
     let close_script = bot_dir.join("close.mjs");
     if !close_script.exists() {
@@ -681,6 +882,7 @@ fn run_close_script(app: &tauri::AppHandle, check_only: bool) -> Result<String,
     }
     let cfg_path = config_dir.join("config.json");
     let env_path = config_dir.join(".env");
+    let key = keystore::key_for_launch(app, &config_dir)?;
 
     let mut cmd = StdCommand::new(&node);
     cmd.arg(close_script.to_str().unwrap())
@@ -696,9 +898,17 @@ fn run_close_script(app: &tauri::AppHandle, check_only: bool) -> Result<String,
     }
     cmd.env("DOTENV_CONFIG_QUIET", "true");
     cmd.env("DATA_DIR", config_dir.to_str().unwrap());
+    expect_key_on_stdin(&mut cmd, key.is_some());
 
-    let output = cmd.output().map_err(|e| format!("Failed to run script: {}", e))?;
-    let raw_stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to run script: {}", e))?;
+    if let Err(e) = send_key(&mut child, key) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(e);
+    }
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run script: {}", e))?;
+    let redactor = redact::Redactor::load(&config_dir);
+    let raw_stdout = redactor.apply(String::from_utf8_lossy(&output.stdout).trim()).into_owned();
     let stdout = raw_stdout
         .lines()
         .rev()
@@ -706,7 +916,7 @@ fn run_close_script(app: &tauri::AppHandle, check_only: bool) -> Result<String,
         .unwrap_or("")
         .to_string();
     if stdout.is_empty() {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let stderr = redactor.apply(String::from_utf8_lossy(&output.stderr).trim()).into_owned();
         return Err(format!("Script failed: {}", stderr));
     }
     Ok(stdout)
@@ -719,11 +929,22 @@ async fn check_position(app: tauri::AppHandle) -> Result<String, String> {
 
 #[tauri::command]
 async fn close_position(app: tauri::AppHandle) -> Result<String, String> {
-    run_close_script(&app, false)
+    let result = run_close_script(&app, false);
+    audit_log::record(&app, "close_position", serde_json::json!({}), result)
 }
 
 #[tauri::command]
 async fn restart_bot(app: tauri::AppHandle, state: State<'_, BotState>) -> Result<(), String> {
+    let result = relaunch_bot(app.clone(), state).await;
+    audit_log::record(&app, "restart_bot", serde_json::json!({}), result)
+}
+
+async fn relaunch_bot(app: tauri::AppHandle, state: State<'_, BotState>) -> Result<(), String> {
+    // Refuse before stopping anything, so a bad config never takes a running bot down
+    let config_dir = bot_config_dir(&app)?;
+    check_config_file(&config_dir.join(config::CONFIG_FILE))?;
+    license::ensure_bot_allowed(&config_dir)?;
+
     // Stop if running
     {
         let mut child_lock = state.child.lock().unwrap();
@@ -732,14 +953,96 @@ async fn restart_bot(app: tauri::AppHandle, state: State<'_, BotState>) -> Resul
             unsafe { libc::kill(child.id() as i32, libc::SIGTERM); }
             std::thread::sleep(std::time::Duration::from_secs(2));
             let _ = child.kill();
-            let _ = child.wait();
+            let code = child.wait().ok().and_then(|s| s.code());
             *state.running.lock().unwrap() = false;
             *child_lock = None;
+            finish_run(&app, code, db::ExitKind::Stopped);
         }
     }
     // Small delay then start
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-    start_bot(app, state)
+    launch_bot(app, state)
+}
+
+/// How a config change reaches a running bot, which only reads config.json at start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum RestartMode {
+    /// Leave the bot alone; the change applies on the next start.
+    #[default]
+    Manual,
+    /// Restart right away. Supersedes a pending `WhenFlat` restart, which is cancelled.
+    Now,
+    /// Restart once state.json shows no open position or entry in progress.
+    WhenFlat,
+}
+
+/// Whether the bot's last persisted state has no open position and no order flow in
+/// progress. The bot sets `busy` before sending an entry order and clears it once
+/// TP/SL are placed, since `activeSide` is only written after the fill. A missing or
+/// unreadable state.json is not flat: there is no telling what the bot is doing.
+fn bot_is_flat(config_dir: &std::path::Path) -> bool {
+    std::fs::read_to_string(config_dir.join("state.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .is_some_and(|st| st["activeSide"].is_null() && st["busy"].is_null())
+}
+
+/// Apply `mode` to the bot if it is running. Returns whether a restart happened or
+/// was scheduled.
+pub(crate) async fn restart_for_config_change(app: &tauri::AppHandle, mode: RestartMode) -> Result<bool, String> {
+    let state = app.state::<BotState>();
+    if mode == RestartMode::Manual || !*state.running.lock().unwrap() {
+        return Ok(false);
+    }
+    if mode == RestartMode::Now {
+        // The relaunch picks up the latest config, so a deferred restart has nothing left to do
+        if std::mem::take(&mut *state.restart_pending.lock().unwrap()) {
+            log::info!("Restarting now — cancelled the pending restart-when-flat");
+        }
+        relaunch_bot(app.clone(), state).await?;
+        return Ok(true);
+    }
+
+    {
+        let mut pending = state.restart_pending.lock().unwrap();
+        if *pending {
+            return Ok(true);
+        }
+        *pending = true;
+    }
+    let config_dir = bot_config_dir(app)?;
+    let h = app.clone();
+    std::thread::spawn(move || {
+        log::info!("Config changed — restarting the bot once it is flat");
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(5));
+            let state = h.state::<BotState>();
+            if !*state.restart_pending.lock().unwrap() {
+                // Cancelled by a `Now` restart
+                return;
+            }
+            if !*state.running.lock().unwrap() {
+                break;
+            }
+            if bot_is_flat(&config_dir) {
+                if !std::mem::take(&mut *state.restart_pending.lock().unwrap()) {
+                    return;
+                }
+                let result = tauri::async_runtime::block_on(relaunch_bot(h.clone(), state));
+                let params = serde_json::json!({ "mode": RestartMode::WhenFlat });
+                match audit_log::record_as(&h, "restart-when-flat", "restart_bot", params, result) {
+                    Ok(()) => {
+                        let _ = h.emit("bot-restarted", "config");
+                    }
+                    Err(e) => log::error!("Deferred restart failed: {}", e),
+                }
+                return;
+            }
+        }
+        *h.state::<BotState>().restart_pending.lock().unwrap() = false;
+    });
+    Ok(true)
 }
 
 // --- App Entry ---
@@ -750,9 +1053,12 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .manage(BotState::default())
+        .manage(keystore::Keystore::default())
+        .manage(license::LicenseMonitor::default())
         .invoke_handler(tauri::generate_handler![
             is_bot_running,
-            validate_license,
+            license::validate_license,
+            license::get_license_status,
             check_node,
             start_bot,
             stop_bot,
@@ -763,9 +1069,52 @@ pub fn run() {
             get_config_dir,
             get_health,
             write_bot_file,
+            config::validate_config,
+            config::write_config,
+            config_history::list_config_versions,
+            config_history::diff_config_versions,
+            config_history::restore_config_version,
+            profiles::list_profiles,
+            profiles::save_profile,
+            profiles::rename_profile,
+            profiles::delete_profile,
+            profiles::activate_profile,
+            bundle::export_config_bundle,
+            bundle::import_config_bundle,
+            config_watch::apply_config_change,
+            settings::get_app_settings,
+            settings::set_app_settings,
+            dotenv::get_env,
+            dotenv::get_env_var,
+            dotenv::set_env_var,
+            dotenv::unset_env_var,
+            dotenv::update_env,
+            keystore::keystore_status,
+            keystore::create_keystore,
+            keystore::unlock_keystore,
+            keystore::lock_keystore,
+            keystore::delete_keystore,
+            keystore::change_keystore_passphrase,
+            keystore::reencrypt_keystore,
+            wallet::check_private_key,
+            security::security_audit,
+            security::repair_permissions,
+            audit_log::verify_audit_log,
+            audit_log::query_audit_log,
             read_bot_file,
             bot_file_exists,
             write_secret_file,
+            send_telegram_test,
+            equity::get_equity_series,
+            db::query_trades,
+            db::pnl_by_exit_reason,
+            db::trade_summary,
+            db::query_events,
+            db::list_runs,
+            db::get_run_config,
+            db::performance_by_config,
+            analytics::get_performance_breakdown,
+            risk_sim::simulate_risk_of_ruin,
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -777,7 +1126,12 @@ pub fn run() {
             }
 
             // Ensure user config directory exists on startup
-            let _ = user_data_dir();
+            let data_dir = user_data_dir()?;
+            app.manage(db::Db::open(&data_dir));
+            app.manage(audit_log::AuditLog::new(&data_dir, app_version(app.handle())));
+            trades::spawn_trade_watcher(app.handle().clone(), data_dir.clone());
+            config_watch::spawn_config_watcher(app.handle().clone(), data_dir.clone());
+            license::spawn_license_monitor(app.handle().clone(), data_dir);
 
             let handle = app.handle().clone();
             let _tray = tauri::tray::TrayIconBuilder::new()
@@ -809,9 +1163,11 @@ pub fn run() {
                             if let Ok(Some(_)) = child.try_wait() { break; }
                         }
                         let _ = child.kill();
-                        let _ = child.wait();
+                        let code = child.wait().ok().and_then(|s| s.code());
                         *state.running.lock().unwrap() = false;
                         *child_lock = None;
+                        drop(child_lock);
+                        finish_run(app_handle, code, db::ExitKind::Stopped);
                     }
                 }
             }
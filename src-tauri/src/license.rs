@@ -0,0 +1,680 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use crate::audit_log;
+use crate::fsutil::{self, WriteOptions};
+use crate::settings::LicenseSettings;
+
+// --- License Validation ---
+//
+// On a successful online check the license API returns an Ed25519-signed token
+// (key, plan, issued-at, expiry). The token is cached in the config dir and verified
+// against the public key compiled into the app, so an offline launch is still a real
+// check until the token expires. Without a usable token a well-formed key only gets
+// a limited, clearly marked grace period, measured from the first unverified launch.
+// A key the server later revokes keeps working briefly so an open trade can finish.
+
+const LICENSE_CACHE_FILE: &str = "license.json";
+const TOKEN_PREFIX: &str = "v1";
+const GRACE_DAYS: i64 = 7;
+const REVOKED_GRACE_HOURS: i64 = 72;
+/// Builds without a public key (dev builds) can't check tokens, so a recent online
+/// check only buys grace, for as long as a token would have lasted.
+const UNSIGNED_VALID_DAYS: i64 = 14;
+/// Allowed clock difference when checking a token's issued-at time.
+const CLOCK_SKEW_SECS: i64 = 300;
+
+/// Hex-encoded Ed25519 public key matching the API's LICENSE_SIGNING_KEY.
+const PUBLIC_KEY_HEX: Option<&str> = option_env!("LICENSE_PUBLIC_KEY");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LicenseState {
+    /// Confirmed by the server or by a cached, signed token.
+    Valid,
+    /// Not verified (offline, or the token lapsed); allowed until `graceUntil`.
+    Grace,
+    /// The grace period ran out without a successful online check.
+    Expired,
+    /// The server withdrew a key it had accepted; allowed until `graceUntil`.
+    Revoked,
+    /// The server rejected the key, or it isn't a license key at all.
+    Invalid,
+    /// No key has been activated on this install.
+    Unlicensed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseCheck {
+    pub valid: bool,
+    pub state: LicenseState,
+    pub plan: Option<String>,
+    /// When the cached token stops vouching for the key offline.
+    pub expires_at: Option<String>,
+    pub grace_until: Option<String>,
+    /// Last time the server confirmed the key.
+    pub validated_at: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl LicenseCheck {
+    fn with_state(state: LicenseState, reason: impl Into<String>) -> Self {
+        Self {
+            valid: false,
+            state,
+            plan: None,
+            expires_at: None,
+            grace_until: None,
+            validated_at: None,
+            reason: Some(reason.into()),
+        }
+    }
+
+    fn invalid(reason: impl Into<String>) -> Self {
+        Self::with_state(LicenseState::Invalid, reason)
+    }
+
+    /// Whether the bot may run (or keep running) under this license.
+    pub fn allows_bot(&self, now: DateTime<Utc>) -> bool {
+        match self.state {
+            LicenseState::Valid | LicenseState::Grace => true,
+            LicenseState::Revoked => parse_time(self.grace_until.as_deref()).is_some_and(|until| now < until),
+            _ => false,
+        }
+    }
+}
+
+/// Signed claims inside a license token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenClaims {
+    pub ver: u32,
+    pub key: String,
+    pub plan: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+impl TokenClaims {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.exp, 0)
+    }
+}
+
+/// What license.json remembers between launches.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LicenseCache {
+    pub key: Option<String>,
+    pub token: Option<String>,
+    pub plan: Option<String>,
+    pub validated_at: Option<String>,
+    /// Start of the current unverified period. Kept per install rather than per key,
+    /// so entering a different key doesn't restart it.
+    pub grace_started_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub revoked_reason: Option<String>,
+}
+
+fn cache_path(dir: &Path) -> PathBuf {
+    dir.join(LICENSE_CACHE_FILE)
+}
+
+pub fn load_cache(dir: &Path) -> LicenseCache {
+    std::fs::read_to_string(cache_path(dir))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_cache(dir: &Path, cache: &LicenseCache) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(cache).map_err(|e| e.to_string())?;
+    fsutil::write_file(&cache_path(dir), text, WriteOptions::SECRET)
+}
+
+/// SB-XXXX-XXXX-XXXX-XXXX, already trimmed and uppercased.
+pub fn is_well_formed(key: &str) -> bool {
+    key.starts_with("SB-")
+        && key.len() == 22
+        && key.split('-').count() == 5
+        && key.split('-').skip(1).all(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+pub fn normalize_key(key: &str) -> String {
+    key.trim().to_uppercase()
+}
+
+fn public_key() -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(PUBLIC_KEY_HEX?.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// Whether this build can verify tokens at all (release builds set LICENSE_PUBLIC_KEY).
+pub fn can_verify() -> bool {
+    public_key().is_some()
+}
+
+/// Check `token`'s signature and claims for `key` at time `now`.
+pub fn verify_token(token: &str, key: &str, now: DateTime<Utc>) -> Result<TokenClaims, String> {
+    let public_key = public_key().ok_or("This build has no license public key")?;
+    verify_token_with(&public_key, token, key, now)
+}
+
+fn verify_token_with(public_key: &VerifyingKey, token: &str, key: &str, now: DateTime<Utc>) -> Result<TokenClaims, String> {
+    let (signing_input, sig_b64) = token.rsplit_once('.').ok_or("Malformed license token")?;
+    let (prefix, payload_b64) = signing_input.split_once('.').ok_or("Malformed license token")?;
+    if prefix != TOKEN_PREFIX {
+        return Err("Unsupported license token version".into());
+    }
+
+    let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    let sig_bytes = b64.decode(sig_b64).map_err(|_| "Malformed license token signature")?;
+    let signature = Signature::from_slice(&sig_bytes).map_err(|_| "Malformed license token signature")?;
+    public_key
+        .verify(signing_input.as_bytes(), &signature)
+        .map_err(|_| "License token signature is invalid")?;
+
+    let payload = b64.decode(payload_b64).map_err(|_| "Malformed license token")?;
+    let claims: TokenClaims = serde_json::from_slice(&payload).map_err(|e| format!("Malformed license token: {}", e))?;
+    if claims.ver != 1 {
+        return Err("Unsupported license token version".into());
+    }
+    if normalize_key(&claims.key) != key {
+        return Err("License token was issued for a different key".into());
+    }
+    if claims.iat > now.timestamp() + CLOCK_SKEW_SECS {
+        return Err("License token is not valid yet; check the system clock".into());
+    }
+    if claims.exp <= now.timestamp() {
+        return Err("License token has expired".into());
+    }
+    Ok(claims)
+}
+
+fn parse_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// What the license server said about a key.
+enum ServerVerdict {
+    /// `claims` is the verified token, when this build can verify one.
+    Accepted { plan: Option<String>, token: Option<String>, claims: Option<TokenClaims> },
+    Rejected(String),
+}
+
+/// Endpoints and client settings from app settings, or the defaults if those are unusable.
+fn endpoint_settings(dir: &Path) -> LicenseSettings {
+    let settings = crate::settings::load(dir).license;
+    match settings.validate() {
+        Ok(()) => settings,
+        Err(e) => {
+            log::warn!("Ignoring license settings: {}", e);
+            LicenseSettings::default()
+        }
+    }
+}
+
+fn http_client(settings: &LicenseSettings) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().timeout(std::time::Duration::from_secs(settings.timeout_secs));
+    if let Some(url) = settings.proxy.as_deref() {
+        let mut proxy = reqwest::Proxy::all(url).map_err(|e| format!("Invalid proxy {}: {}", url, e))?;
+        proxy = proxy.no_proxy(settings.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string));
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| format!("Failed to set up the license client: {}", e))
+}
+
+/// One request to one endpoint. An accepted answer whose token doesn't verify is an
+/// error like any other, so the next endpoint gets a chance.
+async fn ask_endpoint(client: &reqwest::Client, endpoint: &str, key: &str) -> Result<ServerVerdict, String> {
+    let resp = client
+        .post(endpoint)
+        .json(&serde_json::json!({ "key": key }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.status().is_server_error() {
+        return Err(format!("server returned {}", resp.status()));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("unreadable response: {}", e))?;
+    let str_field = |name: &str| body[name].as_str().map(str::to_string);
+    match body["valid"].as_bool() {
+        Some(true) => {
+            // With a public key compiled in, only a signed answer counts: a bare
+            // {"valid":true} is trivial to fake from a local proxy. Without one the
+            // answer is recorded but never makes the license more than grace.
+            let token = str_field("token");
+            let claims = match (can_verify(), token.as_deref()) {
+                (false, _) => None,
+                (true, Some(t)) => Some(verify_token(t, key, Utc::now())?),
+                (true, None) => return Err("response carried no license token".into()),
+            };
+            Ok(ServerVerdict::Accepted { plan: str_field("plan"), token, claims })
+        }
+        Some(false) => Ok(ServerVerdict::Rejected(str_field("reason").unwrap_or_else(|| "License key not recognized".into()))),
+        None => Err("response is not a license check".into()),
+    }
+}
+
+/// Ask each endpoint in turn, retrying with doubling delays, until one gives a
+/// usable answer. The error is the last failure when none does.
+async fn ask_server(settings: &LicenseSettings, key: &str) -> Result<ServerVerdict, String> {
+    let client = http_client(settings)?;
+    let mut last_error = String::from("no license endpoints configured");
+    for endpoint in &settings.endpoints {
+        for attempt in 0..=settings.retries {
+            if attempt > 0 {
+                let delay = settings.retry_delay_ms.saturating_mul(1 << (attempt - 1).min(10));
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            }
+            match ask_endpoint(&client, endpoint, key).await {
+                Ok(verdict) => return Ok(verdict),
+                Err(e) => {
+                    log::warn!("License check via {} failed (attempt {}): {}", endpoint, attempt + 1, e);
+                    last_error = e;
+                }
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// State of the cached license at `now`, without asking the server. Starts the grace
+/// period in `cache` if it is needed and hasn't started yet; `why` explains it.
+fn evaluate(cache: &mut LicenseCache, now: DateTime<Utc>, why: Option<&str>) -> LicenseCheck {
+    let Some(key) = cache.key.clone() else {
+        return LicenseCheck::with_state(LicenseState::Unlicensed, "No license key has been activated");
+    };
+    let validated_at = parse_time(cache.validated_at.as_deref());
+    let mut check = LicenseCheck {
+        plan: cache.plan.clone(),
+        validated_at: cache.validated_at.clone(),
+        ..LicenseCheck::invalid("")
+    };
+
+    if let Some(revoked_at) = parse_time(cache.revoked_at.as_deref()) {
+        let until = revoked_at + Duration::hours(REVOKED_GRACE_HOURS);
+        check.state = LicenseState::Revoked;
+        check.grace_until = Some(until.to_rfc3339());
+        check.reason = Some(format!(
+            "This license was revoked ({}). The bot stops once it is flat after {}.",
+            cache.revoked_reason.as_deref().unwrap_or("no reason given"),
+            until.format("%Y-%m-%d %H:%M UTC")
+        ));
+        return check;
+    }
+
+    let verified = cache
+        .token
+        .as_deref()
+        .ok_or_else(|| "no license token cached".to_string())
+        .and_then(|token| verify_token(token, &key, now));
+    if let Ok(claims) = verified {
+        check.valid = true;
+        check.state = LicenseState::Valid;
+        check.plan = Some(claims.plan.clone()).or(check.plan);
+        check.expires_at = claims.expires_at().map(|t| t.to_rfc3339());
+        check.reason = None;
+        return check;
+    }
+    if !can_verify() {
+        // An unsigned acceptance proves nothing, however recent
+        if let Some(until) = validated_at.map(|t| t + Duration::days(UNSIGNED_VALID_DAYS)).filter(|until| now < *until) {
+            check.state = LicenseState::Grace;
+            check.grace_until = Some(until.to_rfc3339());
+            check.reason = Some(format!(
+                "This build cannot verify license answers. Running unverified until {}.",
+                until.format("%Y-%m-%d %H:%M UTC")
+            ));
+            return check;
+        }
+    }
+    if validated_at.is_none() && !is_well_formed(&key) {
+        check.reason = Some("Invalid license key format".into());
+        return check;
+    }
+
+    let started = match parse_time(cache.grace_started_at.as_deref()) {
+        Some(t) => t,
+        None => {
+            cache.grace_started_at = Some(now.to_rfc3339());
+            now
+        }
+    };
+    let grace_until = started + Duration::days(GRACE_DAYS);
+    let why = why.unwrap_or("The license has not been verified online recently");
+    check.grace_until = Some(grace_until.to_rfc3339());
+    if now >= grace_until {
+        check.state = LicenseState::Expired;
+        check.reason = Some(format!(
+            "{}. The offline grace period ended on {}; connect to the internet to verify your license.",
+            why,
+            grace_until.format("%Y-%m-%d")
+        ));
+    } else {
+        check.state = LicenseState::Grace;
+        check.reason = Some(format!("{}. Running unverified until {}.", why, grace_until.format("%Y-%m-%d %H:%M UTC")));
+    }
+    check
+}
+
+/// The cached license at `now`, persisting a newly started grace period.
+pub fn current(dir: &Path, now: DateTime<Utc>) -> LicenseCheck {
+    let mut cache = load_cache(dir);
+    let grace_started = cache.grace_started_at.clone();
+    let check = evaluate(&mut cache, now, None);
+    if cache.grace_started_at != grace_started {
+        if let Err(e) = save_cache(dir, &cache) {
+            log::warn!("Failed to record license grace period: {}", e);
+        }
+    }
+    check
+}
+
+/// Refuse to start the bot unless the cached license allows it.
+pub fn ensure_bot_allowed(dir: &Path) -> Result<(), String> {
+    let check = current(dir, Utc::now());
+    if check.allows_bot(Utc::now()) {
+        return Ok(());
+    }
+    Err(format!("Refusing to start: {}", check.reason.as_deref().unwrap_or("no valid license")))
+}
+
+/// Record that the server withdrew the cached key, keeping the first revocation time.
+fn revoke(dir: &Path, cache: &mut LicenseCache, reason: String, now: DateTime<Utc>) -> Result<LicenseCheck, String> {
+    if cache.revoked_at.is_none() {
+        log::warn!("License key was revoked: {}", reason);
+        cache.revoked_at = Some(now.to_rfc3339());
+    }
+    cache.revoked_reason = Some(reason);
+    cache.token = None;
+    let result = evaluate(cache, now, None);
+    save_cache(dir, cache)?;
+    Ok(result)
+}
+
+/// Full check of `key`: online when possible, falling back to the cached token or
+/// grace. Returns the result and whether the server gave a usable answer. The key
+/// only replaces the cached one when it is accepted or allowed into grace. Dropping
+/// or revoking the cached key is audited as `actor`.
+pub async fn check(app: &tauri::AppHandle, actor: &str, dir: &Path, key: &str) -> Result<(LicenseCheck, bool), String> {
+    let key = normalize_key(key);
+    if key.is_empty() {
+        return Ok((LicenseCheck::invalid("Missing license key"), false));
+    }
+    let now = Utc::now();
+    let stored = load_cache(dir);
+    let same_key = stored.key.as_deref() == Some(key.as_str());
+    let mut cache = if same_key {
+        stored
+    } else {
+        LicenseCache { key: Some(key.clone()), grace_started_at: stored.grace_started_at, ..Default::default() }
+    };
+
+    let why = match ask_server(&endpoint_settings(dir), &key).await {
+        Ok(ServerVerdict::Rejected(reason)) => {
+            if !same_key {
+                return Ok((LicenseCheck::invalid(reason), true));
+            }
+            let params = serde_json::json!({ "key": audit_log::mask(&key, true), "reason": &reason });
+            if cache.validated_at.is_none() {
+                // Never confirmed in the first place: drop it rather than grant a grace period.
+                cache = LicenseCache { grace_started_at: cache.grace_started_at, ..Default::default() };
+                let dropped = save_cache(dir, &cache).map(|()| LicenseCheck::invalid(reason));
+                return audit_log::record_as(app, actor, "drop_license", params, dropped).map(|r| (r, true));
+            }
+            let result = revoke(dir, &mut cache, reason, now);
+            return audit_log::record_as(app, actor, "revoke_license", params, result).map(|r| (r, true));
+        }
+        Ok(ServerVerdict::Accepted { plan, token, claims }) => {
+            cache = LicenseCache {
+                key: Some(key.clone()),
+                token,
+                plan: claims.map(|c| c.plan).or(plan),
+                validated_at: Some(now.to_rfc3339()),
+                ..Default::default()
+            };
+            save_cache(dir, &cache)?;
+            return Ok((evaluate(&mut cache, now, None), true));
+        }
+        Err(e) => format!("The license server could not be reached or verified ({})", e),
+    };
+    let result = evaluate(&mut cache, now, Some(&why));
+    if same_key || matches!(result.state, LicenseState::Valid | LicenseState::Grace) {
+        save_cache(dir, &cache)?;
+    }
+    Ok((result, false))
+}
+
+// --- License Monitor ---
+//
+// A running bot may go weeks without the app being reopened, so the license is
+// rechecked in the background: every few hours when the server answers, with
+// exponential backoff when it doesn't. Once the license no longer allows running, the
+// bot is stopped the next time state.json shows it flat, never with a position open.
+
+const RECHECK_SECS: i64 = 6 * 60 * 60;
+const RETRY_BASE_SECS: i64 = 60;
+const RETRY_MAX_SECS: i64 = 60 * 60;
+const MONITOR_TICK_SECS: u64 = 30;
+/// Audit log actor for what the monitor does on its own.
+const MONITOR_ACTOR: &str = "license-monitor";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseStatus {
+    #[serde(flatten)]
+    pub check: LicenseCheck,
+    pub bot_allowed: bool,
+    pub last_checked_at: Option<String>,
+    pub next_check_at: Option<String>,
+    /// Online checks that failed since the last good one.
+    pub failed_checks: u32,
+}
+
+#[derive(Default)]
+struct Schedule {
+    last_checked_at: Option<DateTime<Utc>>,
+    next_check_at: Option<DateTime<Utc>>,
+    failed_checks: u32,
+}
+
+/// Managed state: when the license was last checked online and when it is next due.
+#[derive(Default)]
+pub struct LicenseMonitor {
+    schedule: Mutex<Schedule>,
+}
+
+impl LicenseMonitor {
+    fn due(&self, now: DateTime<Utc>) -> bool {
+        self.schedule.lock().unwrap().next_check_at.map_or(true, |t| now >= t)
+    }
+
+    /// Schedule the next check after an attempt that did or didn't reach the server.
+    fn record(&self, now: DateTime<Utc>, reached: bool) {
+        let mut s = self.schedule.lock().unwrap();
+        s.last_checked_at = Some(now);
+        let delay = if reached {
+            s.failed_checks = 0;
+            RECHECK_SECS
+        } else {
+            s.failed_checks += 1;
+            (RETRY_BASE_SECS << (s.failed_checks - 1).min(10)).min(RETRY_MAX_SECS)
+        };
+        s.next_check_at = Some(now + Duration::seconds(delay));
+    }
+
+    pub fn status(&self, dir: &Path, now: DateTime<Utc>) -> LicenseStatus {
+        let check = current(dir, now);
+        let s = self.schedule.lock().unwrap();
+        LicenseStatus {
+            bot_allowed: check.allows_bot(now),
+            check,
+            last_checked_at: s.last_checked_at.map(|t| t.to_rfc3339()),
+            next_check_at: s.next_check_at.map(|t| t.to_rfc3339()),
+            failed_checks: s.failed_checks,
+        }
+    }
+}
+
+/// Stop the bot if it is running and flat. Returns whether it was stopped.
+fn stop_when_flat(app: &tauri::AppHandle, dir: &Path, reason: &str) -> bool {
+    let state = app.state::<crate::BotState>();
+    if !*state.running.lock().unwrap() || !crate::bot_is_flat(dir) {
+        return false;
+    }
+    log::warn!("Stopping the bot: {}", reason);
+    let result = crate::terminate_bot(app, state);
+    let params = serde_json::json!({ "reason": reason });
+    if let Err(e) = audit_log::record_as(app, MONITOR_ACTOR, "stop_bot", params, result) {
+        log::error!("Failed to stop the bot for the license: {}", e);
+        return false;
+    }
+    let event = serde_json::json!({"type": "error", "message": format!("Bot stopped: {}", reason)});
+    let _ = app.emit("bot-event", &event.to_string());
+    let event = serde_json::json!({"type": "stopped", "code": 0});
+    let _ = app.emit("bot-event", &event.to_string());
+    true
+}
+
+/// Recheck the license on schedule, emit `license-status` when it changes, and stop
+/// the bot once the license no longer allows it.
+pub fn spawn_license_monitor(app: tauri::AppHandle, dir: PathBuf) {
+    std::thread::spawn(move || {
+        let mut last_sent: Option<(LicenseState, Option<String>)> = None;
+        let mut waiting_for_flat = false;
+        loop {
+            let monitor = app.state::<LicenseMonitor>();
+            let now = Utc::now();
+            if let Some(key) = load_cache(&dir).key {
+                if monitor.due(now) {
+                    match tauri::async_runtime::block_on(check(&app, MONITOR_ACTOR, &dir, &key)) {
+                        Ok((_, reached)) => monitor.record(now, reached),
+                        Err(e) => {
+                            log::warn!("License check failed: {}", e);
+                            monitor.record(now, false);
+                        }
+                    }
+                }
+            }
+
+            let status = monitor.status(&dir, Utc::now());
+            let sent = (status.check.state, status.check.grace_until.clone());
+            if last_sent.as_ref() != Some(&sent) {
+                let _ = app.emit("license-status", &status);
+                last_sent = Some(sent);
+            }
+            if status.bot_allowed {
+                waiting_for_flat = false;
+            } else {
+                let reason = status.check.reason.as_deref().unwrap_or("the license is no longer valid");
+                if stop_when_flat(&app, &dir, reason) {
+                    waiting_for_flat = false;
+                } else if !waiting_for_flat && *app.state::<crate::BotState>().running.lock().unwrap() {
+                    log::warn!("License no longer allows running; stopping the bot once it is flat");
+                    waiting_for_flat = true;
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_secs(MONITOR_TICK_SECS));
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn validate_license(app: tauri::AppHandle, key: String) -> Result<LicenseCheck, String> {
+    let result = validate(&app, &key).await;
+    let params = serde_json::json!({ "key": audit_log::mask(&key, true) });
+    audit_log::record(&app, "validate_license", params, result)
+}
+
+async fn validate(app: &tauri::AppHandle, key: &str) -> Result<LicenseCheck, String> {
+    let dir = crate::user_data_dir()?;
+    let (result, reached) = check(app, audit_log::USER, &dir, key).await?;
+    if matches!(result.state, LicenseState::Valid | LicenseState::Grace) {
+        app.state::<LicenseMonitor>().record(Utc::now(), reached);
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_license_status(monitor: tauri::State<LicenseMonitor>) -> Result<LicenseStatus, String> {
+    Ok(monitor.status(&crate::user_data_dir()?, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const KEY: &str = "SB-ABCD-EFGH-JKLM-NPQR";
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    /// A token in the API's format (api/lib/token.mjs).
+    fn token(signer: &SigningKey, key: &str, iat: i64, exp: i64) -> String {
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let claims = serde_json::json!({ "ver": 1, "key": key, "plan": "pro", "iat": iat, "exp": exp });
+        let signing_input = format!("{}.{}", TOKEN_PREFIX, b64.encode(claims.to_string()));
+        let signature = signer.sign(signing_input.as_bytes());
+        format!("{}.{}", signing_input, b64.encode(signature.to_bytes()))
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn accepts_a_good_token() {
+        let signer = signing_key(1);
+        let t = token(&signer, KEY, now().timestamp() - 60, now().timestamp() + 3600);
+        let claims = verify_token_with(&signer.verifying_key(), &t, KEY, now()).unwrap();
+        assert_eq!(claims.plan, "pro");
+    }
+
+    #[test]
+    fn rejects_a_token_signed_with_another_key() {
+        let t = token(&signing_key(2), KEY, now().timestamp() - 60, now().timestamp() + 3600);
+        let err = verify_token_with(&signing_key(1).verifying_key(), &t, KEY, now()).unwrap_err();
+        assert_eq!(err, "License token signature is invalid");
+    }
+
+    #[test]
+    fn rejects_an_expired_token() {
+        let signer = signing_key(1);
+        let t = token(&signer, KEY, now().timestamp() - 7200, now().timestamp() - 1);
+        let err = verify_token_with(&signer.verifying_key(), &t, KEY, now()).unwrap_err();
+        assert_eq!(err, "License token has expired");
+    }
+
+    #[test]
+    fn rejects_a_tampered_or_mangled_signature() {
+        let signer = signing_key(1);
+        let t = token(&signer, KEY, now().timestamp() - 60, now().timestamp() + 3600);
+        let (signing_input, sig) = t.rsplit_once('.').unwrap();
+
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let mut bytes = b64.decode(sig).unwrap();
+        bytes[0] ^= 1;
+        let flipped = format!("{}.{}", signing_input, b64.encode(bytes));
+        let err = verify_token_with(&signer.verifying_key(), &flipped, KEY, now()).unwrap_err();
+        assert_eq!(err, "License token signature is invalid");
+
+        let truncated = format!("{}.{}", signing_input, &sig[..10]);
+        let err = verify_token_with(&signer.verifying_key(), &truncated, KEY, now()).unwrap_err();
+        assert_eq!(err, "Malformed license token signature");
+    }
+
+    #[test]
+    fn rejects_a_token_for_another_key() {
+        let signer = signing_key(1);
+        let t = token(&signer, "SB-ZZZZ-ZZZZ-ZZZZ-ZZZZ", now().timestamp() - 60, now().timestamp() + 3600);
+        let err = verify_token_with(&signer.verifying_key(), &t, KEY, now()).unwrap_err();
+        assert_eq!(err, "License token was issued for a different key");
+    }
+}
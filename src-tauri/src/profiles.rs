@@ -0,0 +1,227 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::audit_log;
+use crate::config::{self, ConfigIssue, CONFIG_FILE};
+use crate::config_history::{self, ConfigSource};
+use crate::fsutil::{self, WriteOptions};
+use crate::RestartMode;
+
+// --- Config Profiles ---
+//
+// A profile is config.json minus the sections that belong to the install rather than
+// the strategy. Those stay shared: activating a profile keeps whatever wallet and
+// telegram settings the live config has.
+
+const PROFILES_DIR: &str = "profiles";
+const SHARED_SECTIONS: &[&str] = &["wallet", "telegram"];
+const MAX_NAME_LEN: usize = 40;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub updated_ms: Option<i64>,
+    /// The live config currently matches this profile.
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileActivation {
+    pub name: String,
+    pub restart: RestartMode,
+    /// A restart happened, or is waiting for the position to close.
+    pub restarting: bool,
+    pub warnings: Vec<ConfigIssue>,
+}
+
+fn profiles_dir(dir: &Path) -> PathBuf {
+    dir.join(PROFILES_DIR)
+}
+
+/// Names become file names, so keep them to a safe, portable character set.
+fn check_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!("Profile names must be 1 to {} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+        return Err("Profile names may only contain letters, numbers, spaces, '-' and '_'".into());
+    }
+    Ok(name.to_string())
+}
+
+fn profile_path(dir: &Path, name: &str) -> PathBuf {
+    profiles_dir(dir).join(format!("{}.json", name))
+}
+
+fn profile_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(profiles_dir(dir))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(".json").map(str::to_string))
+        .filter(|n| check_name(n).is_ok())
+        .collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    names
+}
+
+/// Existing profile whose name differs from `name` only by case. Case-insensitive
+/// filesystems would otherwise silently merge the two.
+fn existing_name(dir: &Path, name: &str) -> Option<String> {
+    profile_names(dir).into_iter().find(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn read_json(path: &Path) -> Result<serde_json::Value, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("{} is not valid JSON: {}", path.display(), e))
+}
+
+fn load_profile(dir: &Path, name: &str) -> Result<serde_json::Value, String> {
+    let name = check_name(name)?;
+    let path = profile_path(dir, &name);
+    if !path.is_file() {
+        return Err(format!("No profile named '{}'", name));
+    }
+    let mut profile = read_json(&path)?;
+    config::migrate(&mut profile, &mut Vec::new())?;
+    Ok(profile)
+}
+
+fn strip_shared(mut cfg: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = cfg.as_object_mut() {
+        for key in SHARED_SECTIONS {
+            obj.remove(*key);
+        }
+    }
+    cfg
+}
+
+fn live_config(dir: &Path) -> Option<serde_json::Value> {
+    read_json(&dir.join(CONFIG_FILE)).ok()
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub fn list_profiles(app: tauri::AppHandle) -> Result<Vec<ProfileInfo>, String> {
+    let dir = crate::bot_config_dir(&app)?;
+    let live = live_config(&dir).map(strip_shared);
+    Ok(profile_names(&dir)
+        .into_iter()
+        .map(|name| {
+            let updated_ms = std::fs::metadata(profile_path(&dir, &name))
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64);
+            let active = live.is_some() && load_profile(&dir, &name).ok().map(strip_shared) == live;
+            ProfileInfo { name, updated_ms, active }
+        })
+        .collect())
+}
+
+/// Save the live config.json as profile `name`. An existing profile of that name is
+/// only replaced when `overwrite` is set.
+#[tauri::command]
+pub fn save_profile(app: tauri::AppHandle, name: String, overwrite: Option<bool>) -> Result<(), String> {
+    let result = save(&app, &name, overwrite.unwrap_or(false));
+    let params = serde_json::json!({ "name": name, "overwrite": overwrite });
+    audit_log::record(&app, "save_profile", params, result)
+}
+
+fn save(app: &tauri::AppHandle, name: &str, overwrite: bool) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    let name = check_name(name)?;
+    if let Some(existing) = existing_name(&dir, &name) {
+        if !overwrite {
+            return Err(format!("A profile named '{}' already exists", existing));
+        }
+        if existing != name {
+            let _ = std::fs::remove_file(profile_path(&dir, &existing));
+        }
+    }
+    let live = live_config(&dir).ok_or("config.json not found. Complete setup first.")?;
+    std::fs::create_dir_all(profiles_dir(&dir)).map_err(|e| format!("Failed to create profiles folder: {}", e))?;
+    let text = serde_json::to_string_pretty(&strip_shared(live)).map_err(|e| e.to_string())?;
+    let path = profile_path(&dir, &name);
+    fsutil::write_file(&path, text, WriteOptions::BACKUP)
+}
+
+#[tauri::command]
+pub fn rename_profile(app: tauri::AppHandle, from: String, to: String) -> Result<(), String> {
+    let result = rename(&app, &from, &to);
+    audit_log::record(&app, "rename_profile", serde_json::json!({ "from": from, "to": to }), result)
+}
+
+fn rename(app: &tauri::AppHandle, from: &str, to: &str) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    let from = check_name(from)?;
+    let to = check_name(to)?;
+    let src = profile_path(&dir, &from);
+    if !src.is_file() {
+        return Err(format!("No profile named '{}'", from));
+    }
+    if let Some(existing) = existing_name(&dir, &to).filter(|n| *n != from) {
+        return Err(format!("A profile named '{}' already exists", existing));
+    }
+    std::fs::rename(&src, profile_path(&dir, &to)).map_err(|e| format!("Failed to rename profile: {}", e))
+}
+
+#[tauri::command]
+pub fn delete_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let result = delete(&app, &name);
+    audit_log::record(&app, "delete_profile", serde_json::json!({ "name": name }), result)
+}
+
+fn delete(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let dir = crate::bot_config_dir(app)?;
+    let name = check_name(name)?;
+    let path = profile_path(&dir, &name);
+    if !path.is_file() {
+        return Err(format!("No profile named '{}'", name));
+    }
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete profile: {}", e))
+}
+
+/// Make profile `name` the live config, keeping the live wallet and telegram
+/// sections. `restart` decides whether a running bot picks it up now, once flat,
+/// or on its next start.
+#[tauri::command]
+pub async fn activate_profile(
+    app: tauri::AppHandle,
+    name: String,
+    restart: Option<RestartMode>,
+) -> Result<ProfileActivation, String> {
+    let params = serde_json::json!({ "name": &name, "restart": restart });
+    let result = activate(&app, &name, restart.unwrap_or_default()).await;
+    audit_log::record(&app, "activate_profile", params, result)
+}
+
+async fn activate(app: &tauri::AppHandle, name: &str, restart: RestartMode) -> Result<ProfileActivation, String> {
+    let dir = crate::bot_config_dir(app)?;
+    let mut cfg = load_profile(&dir, name)?;
+    let obj = cfg.as_object_mut().ok_or("Profile is not a JSON object")?;
+    for key in SHARED_SECTIONS {
+        obj.remove(*key);
+    }
+    if let Some(live) = live_config(&dir) {
+        for key in SHARED_SECTIONS {
+            if let Some(section) = live.get(*key) {
+                obj.insert(key.to_string(), section.clone());
+            }
+        }
+    }
+
+    let report = config::check(&cfg);
+    if !report.valid {
+        return Err(report.error_summary());
+    }
+    let text = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+    config_history::write_config_file(&dir, &text, ConfigSource::Profile)?;
+    log::info!("Activated config profile '{}'", name.trim());
+
+    let restarting = crate::restart_for_config_change(app, restart).await?;
+    Ok(ProfileActivation { name: name.trim().to_string(), restart, restarting, warnings: report.warnings })
+}
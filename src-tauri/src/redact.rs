@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+use zeroize::Zeroizing;
+use crate::dotenv::{self, EnvFile, ENV_FILE};
+
+// --- Secret Redaction ---
+//
+// Everything the bot prints passes through here before it is emitted to the webview,
+// stored in the events table or kept as `last_error`. The SDK and our own error
+// paths can echo a key or token back, and none of those sinks should ever hold one.
+// Masking is by shape (hex keys, Telegram tokens, license keys) plus the exact
+// secret values in use, so a secret in an unexpected format is still caught.
+
+/// Secret files whose contents are masked verbatim, besides the .env secrets and
+/// the license key kept in license.json.
+const SECRET_FILES: &[&str] = &["tg_token"];
+/// Shorter values (`true`, `BTC`) would mask ordinary words.
+const MIN_LITERAL_LEN: usize = 8;
+
+struct Pattern {
+    regex: Regex,
+    replacement: &'static str,
+}
+
+fn patterns() -> &'static [Pattern] {
+    static PATTERNS: OnceLock<Vec<Pattern>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let pattern = |re: &str, replacement| Pattern { regex: Regex::new(re).unwrap(), replacement };
+        vec![
+            // 32-byte hex: private keys, but also tx hashes, which are masked to be safe
+            pattern(r"\b(?:0x)?[0-9a-fA-F]{64}\b", "[REDACTED KEY]"),
+            // Telegram bot tokens, bare or inside an API URL (`/bot<id>:<token>/`)
+            pattern(r"\b(bot)?(\d{6,12}):[A-Za-z0-9_-]{30,}", "${1}${2}:[REDACTED]"),
+            pattern(r"(?i)\bSB(?:-[A-Z0-9]{4}){4}\b", "SB-****-****-****-****"),
+        ]
+    })
+}
+
+/// Masks secrets in text. Built once per bot launch from the secrets on disk then.
+#[derive(Default)]
+pub struct Redactor {
+    literals: Vec<Zeroizing<String>>,
+}
+
+impl Redactor {
+    /// Collect the secret values currently in .env, the secret files and the license
+    /// key in `dir`.
+    pub fn load(dir: &Path) -> Self {
+        let mut literals: Vec<Zeroizing<String>> = Vec::new();
+        if let Ok(env) = EnvFile::load(&dir.join(ENV_FILE)) {
+            literals.extend(env.entries().filter(|(k, _)| dotenv::is_secret_key(k)).map(|(_, v)| Zeroizing::new(v.to_string())));
+        }
+        for name in SECRET_FILES {
+            if let Ok(contents) = std::fs::read_to_string(dir.join(name)) {
+                literals.push(Zeroizing::new(contents.trim().to_string()));
+            }
+        }
+        if let Some(key) = crate::license::load_cache(dir).key {
+            literals.push(Zeroizing::new(key));
+        }
+        literals.retain(|v| v.len() >= MIN_LITERAL_LEN);
+        // Longest first, so a secret containing another is masked whole
+        literals.sort_by_key(|v| std::cmp::Reverse(v.len()));
+        literals.dedup();
+        Self { literals }
+    }
+
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        for literal in &self.literals {
+            if out.contains(literal.as_str()) {
+                out = Cow::Owned(out.replace(literal.as_str(), "[REDACTED]"));
+            }
+        }
+        for p in patterns() {
+            if let Cow::Owned(replaced) = p.regex.replace_all(&out, p.replacement) {
+                out = Cow::Owned(replaced);
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,313 @@
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use crate::trades::{self, RoundTrip};
+
+// --- Monte Carlo Risk of Ruin ---
+//
+// Bootstraps completed trades from trades.jsonl into thousands of alternative
+// orderings to show how deep drawdowns could plausibly get.
+//
+// `r` mode re-sizes every trade for the equity at that point: a trade's R-multiple is
+// its return on notional divided by the configured stop distance, and it is replayed
+// as `R * equity * riskPerTradePct` (capped by maxLeverage). That lets the user try a
+// higher risk or leverage setting against the same edge. `pnl` mode replays raw USD
+// results at the size they were actually taken.
+
+const DEFAULT_SIMULATIONS: u32 = 5000;
+const MAX_SIMULATIONS: u32 = 100_000;
+const MAX_TRADES_PER_SIM: u32 = 10_000;
+const MIN_TRADES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleMode {
+    #[default]
+    R,
+    Pnl,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimulationParams {
+    pub mode: SampleMode,
+    pub simulations: Option<u32>,
+    /// Trades per simulated sequence; defaults to the number of historical trades.
+    pub trades_per_sim: Option<u32>,
+    /// Defaults to the latest recorded equity.
+    pub starting_equity: Option<f64>,
+    /// Overrides `risk.riskPerTradePct` (r mode).
+    pub risk_per_trade_pct: Option<f64>,
+    /// Overrides `risk.maxLeverage` (r mode).
+    pub max_leverage: Option<f64>,
+    /// Overrides `risk.maxDailyLossUsd`.
+    pub max_daily_loss_usd: Option<f64>,
+    /// Equity at or below which a sequence counts as ruined; defaults to half the
+    /// starting equity.
+    pub ruin_threshold_usd: Option<f64>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Percentiles {
+    pub p1: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+impl Percentiles {
+    fn of(values: &mut [f64]) -> Self {
+        values.sort_by(|a, b| a.total_cmp(b));
+        let at = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+        Self { p1: at(0.01), p5: at(0.05), p25: at(0.25), p50: at(0.5), p75: at(0.75), p95: at(0.95), p99: at(0.99) }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    pub mode: SampleMode,
+    pub simulations: u32,
+    pub trades_per_sim: u32,
+    pub sample_trades: usize,
+    pub trades_per_day: f64,
+    pub starting_equity: f64,
+    pub risk_per_trade_pct: Option<f64>,
+    pub max_leverage: Option<f64>,
+    pub stop_pct: Option<f64>,
+    pub final_equity: Percentiles,
+    pub max_drawdown_pct: Percentiles,
+    pub max_drawdown_usd: Percentiles,
+    pub max_daily_loss_usd: Option<f64>,
+    /// Share of sequences that tripped the daily-loss halt at least once.
+    pub prob_daily_halt: f64,
+    pub avg_halts_per_sim: f64,
+    pub ruin_threshold_usd: f64,
+    pub prob_ruin: f64,
+    pub elapsed_ms: u64,
+}
+
+fn cfg_f64(cfg: &serde_json::Value, section: &str, key: &str) -> Option<f64> {
+    cfg[section][key].as_f64().filter(|v| v.is_finite() && *v > 0.0)
+}
+
+/// Stop distance the bot would use with this config: the tightest of
+/// `exits.stopLossPct`, `exits.maxMarginLossPct / maxLeverage` and `signal.maxStopPct`.
+fn configured_stop_pct(cfg: &serde_json::Value, leverage: Option<f64>) -> Option<f64> {
+    let mut caps = vec![cfg_f64(cfg, "exits", "stopLossPct"), cfg_f64(cfg, "signal", "maxStopPct")];
+    if let (Some(m), Some(lev)) = (cfg_f64(cfg, "exits", "maxMarginLossPct"), leverage) {
+        caps.push(Some(m / lev));
+    }
+    caps.into_iter().flatten().reduce(f64::min)
+}
+
+/// Average completed trades per calendar day over the span of the history.
+fn trades_per_day(trips: &[RoundTrip]) -> f64 {
+    let first = trips.iter().map(|t| t.entry_ts_ms()).min().unwrap_or(0);
+    let last = trips.iter().map(|t| t.close_ts_ms).max().unwrap_or(0);
+    let days = ((last - first) as f64 / 86_400_000.0).max(1.0);
+    (trips.len() as f64 / days).max(1.0)
+}
+
+struct SimConfig {
+    mode: SampleMode,
+    samples: Vec<f64>,
+    simulations: u32,
+    trades_per_sim: u32,
+    trades_per_day: f64,
+    starting_equity: f64,
+    /// Fraction of equity risked per trade after the leverage cap (r mode).
+    risk_fraction: f64,
+    max_daily_loss: Option<f64>,
+    ruin_threshold: f64,
+}
+
+struct SimOutcome {
+    final_equity: Vec<f64>,
+    max_dd_pct: Vec<f64>,
+    max_dd_usd: Vec<f64>,
+    sims_halted: u32,
+    total_halts: u64,
+    sims_ruined: u32,
+}
+
+fn run_simulations(cfg: &SimConfig, rng: &mut SmallRng) -> SimOutcome {
+    let n = cfg.simulations as usize;
+    let mut out = SimOutcome {
+        final_equity: Vec::with_capacity(n),
+        max_dd_pct: Vec::with_capacity(n),
+        max_dd_usd: Vec::with_capacity(n),
+        sims_halted: 0,
+        total_halts: 0,
+        sims_ruined: 0,
+    };
+
+    for _ in 0..n {
+        let mut equity = cfg.starting_equity;
+        let mut peak = equity;
+        let (mut dd_pct, mut dd_usd) = (0.0_f64, 0.0_f64);
+        let mut day = 0_u64;
+        let mut day_pnl = 0.0;
+        let mut halted_today = false;
+        let (mut halts, mut ruined) = (0_u64, false);
+
+        for i in 0..cfg.trades_per_sim {
+            let today = (i as f64 / cfg.trades_per_day).floor() as u64;
+            if today != day {
+                day = today;
+                day_pnl = 0.0;
+                halted_today = false;
+            }
+            // The bot stops trading for the rest of the UTC day after a halt
+            if halted_today {
+                continue;
+            }
+
+            let sample = cfg.samples[rng.gen_range(0..cfg.samples.len())];
+            let pnl = match cfg.mode {
+                SampleMode::R => sample * equity * cfg.risk_fraction,
+                SampleMode::Pnl => sample,
+            };
+            equity += pnl;
+            day_pnl += pnl;
+
+            peak = peak.max(equity);
+            dd_usd = dd_usd.max(peak - equity);
+            if peak > 0.0 {
+                dd_pct = dd_pct.max((peak - equity) / peak);
+            }
+            if equity <= cfg.ruin_threshold {
+                ruined = true;
+                break;
+            }
+            if cfg.max_daily_loss.is_some_and(|limit| day_pnl <= -limit) {
+                halted_today = true;
+                halts += 1;
+            }
+        }
+
+        out.final_equity.push(equity);
+        out.max_dd_pct.push(dd_pct);
+        out.max_dd_usd.push(dd_usd);
+        out.total_halts += halts;
+        if halts > 0 {
+            out.sims_halted += 1;
+        }
+        if ruined {
+            out.sims_ruined += 1;
+        }
+    }
+    out
+}
+
+/// Overrides that must be a positive number when given.
+fn check_positive(name: &str, value: Option<f64>) -> Result<(), String> {
+    match value {
+        Some(v) if !v.is_finite() || v <= 0.0 => Err(format!("{} must be a positive number (got {})", name, v)),
+        _ => Ok(()),
+    }
+}
+
+/// Bootstrap realised trades into many simulated sequences and report the spread of
+/// final equity and max drawdown, plus the odds of a daily-loss halt or ruin.
+#[tauri::command]
+pub async fn simulate_risk_of_ruin(app: tauri::AppHandle, params: Option<SimulationParams>) -> Result<SimulationReport, String> {
+    let params = params.unwrap_or_default();
+    check_positive("startingEquity", params.starting_equity)?;
+    check_positive("riskPerTradePct", params.risk_per_trade_pct)?;
+    check_positive("maxLeverage", params.max_leverage)?;
+    let config_dir = crate::bot_config_dir(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let started = std::time::Instant::now();
+        let cfg: serde_json::Value = std::fs::read_to_string(config_dir.join("config.json"))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        let trips = trades::round_trips(&trades::load_trade_log(&config_dir)?);
+        if trips.len() < MIN_TRADES {
+            return Err(format!(
+                "Need at least {} completed trades to simulate (found {})",
+                MIN_TRADES,
+                trips.len()
+            ));
+        }
+
+        let starting_equity = params
+            .starting_equity
+            .or_else(|| crate::equity::latest_equity(&config_dir))
+            .filter(|e| *e > 0.0)
+            .ok_or("No equity recorded yet — pass a starting equity")?;
+        let leverage = params.max_leverage.or_else(|| cfg_f64(&cfg, "risk", "maxLeverage"));
+        let risk_pct = params.risk_per_trade_pct.or_else(|| cfg_f64(&cfg, "risk", "riskPerTradePct"));
+        let stop_pct = configured_stop_pct(&cfg, cfg_f64(&cfg, "risk", "maxLeverage"));
+
+        let (samples, risk_fraction) = match params.mode {
+            SampleMode::Pnl => (trips.iter().map(|t| t.pnl_usd).collect::<Vec<_>>(), 0.0),
+            SampleMode::R => {
+                let stop = stop_pct.ok_or("config.json has no stop distance (exits.stopLossPct / signal.maxStopPct) to derive R from")?;
+                let risk = risk_pct.ok_or("Set risk.riskPerTradePct or pass riskPerTradePct")?;
+                let samples: Vec<f64> = trips
+                    .iter()
+                    .filter_map(|t| t.notional_usd.filter(|n| *n > 0.0).map(|n| t.pnl_usd / n / stop))
+                    .collect();
+                if samples.len() < MIN_TRADES {
+                    return Err("Too few trades with a recorded notional for R mode — try pnl mode".into());
+                }
+                // notional = risk / stop may not exceed equity * leverage
+                let fraction = match leverage {
+                    Some(lev) => risk.min(lev * stop),
+                    None => risk,
+                };
+                (samples, fraction)
+            }
+        };
+
+        let sim = SimConfig {
+            mode: params.mode,
+            trades_per_sim: params.trades_per_sim.unwrap_or(trips.len() as u32).clamp(1, MAX_TRADES_PER_SIM),
+            simulations: params.simulations.unwrap_or(DEFAULT_SIMULATIONS).clamp(1, MAX_SIMULATIONS),
+            trades_per_day: trades_per_day(&trips),
+            starting_equity,
+            risk_fraction,
+            max_daily_loss: params.max_daily_loss_usd.or_else(|| cfg_f64(&cfg, "risk", "maxDailyLossUsd")),
+            ruin_threshold: params.ruin_threshold_usd.unwrap_or(starting_equity * 0.5),
+            samples,
+        };
+        let mut rng = match params.seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_entropy(),
+        };
+        let mut out = run_simulations(&sim, &mut rng);
+
+        let sims = sim.simulations as f64;
+        Ok(SimulationReport {
+            mode: sim.mode,
+            simulations: sim.simulations,
+            trades_per_sim: sim.trades_per_sim,
+            sample_trades: sim.samples.len(),
+            trades_per_day: sim.trades_per_day,
+            starting_equity,
+            risk_per_trade_pct: if sim.mode == SampleMode::R { risk_pct } else { None },
+            max_leverage: leverage,
+            stop_pct,
+            final_equity: Percentiles::of(&mut out.final_equity),
+            max_drawdown_pct: Percentiles::of(&mut out.max_dd_pct),
+            max_drawdown_usd: Percentiles::of(&mut out.max_dd_usd),
+            max_daily_loss_usd: sim.max_daily_loss,
+            prob_daily_halt: out.sims_halted as f64 / sims,
+            avg_halts_per_sim: out.total_halts as f64 / sims,
+            ruin_threshold_usd: sim.ruin_threshold,
+            prob_ruin: out.sims_ruined as f64 / sims,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        })
+    })
+    .await
+    .map_err(|e| format!("Simulation failed: {}", e))?
+}
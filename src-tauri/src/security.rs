@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::dotenv::{EnvFile, ENV_FILE};
+
+// --- Permission Audit ---
+//
+// Checks that the data dir and everything sensitive in it (plus key and token files
+// configured to live elsewhere) are owned by the current user and closed to group and
+// others. Secrets must be 0600; the trading history and state only need to be
+// unreadable by others. Repair tightens modes but never changes ownership, which
+// needs an administrator.
+
+/// Credentials and their backups.
+const SECRET_FILES: &[&str] = &[
+    ".env", ".env.bak", "private_key", "private_key.bak", "tg_token", "tg_token.bak",
+    "keystore.json", "license.key", "license.json",
+];
+/// Not credentials, but positions, balances and history.
+const PRIVATE_FILES: &[&str] = &["config.json", "config.json.bak", "state.json", "trades.jsonl", "signalbot.db", "audit.jsonl"];
+/// Folders of config snapshots and exports.
+const PRIVATE_DIRS: &[&str] = &["config-history", "profiles", "exports"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Sensitivity {
+    Directory,
+    Secret,
+    Private,
+}
+
+impl Sensitivity {
+    #[cfg(unix)]
+    fn expected_mode(self) -> u32 {
+        match self {
+            Sensitivity::Directory => 0o700,
+            Sensitivity::Secret => 0o600,
+            Sensitivity::Private => 0o600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Ok,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditItem {
+    pub path: PathBuf,
+    pub sensitivity: Sensitivity,
+    /// Octal, e.g. `0644`. `None` where modes don't apply.
+    pub mode: Option<String>,
+    pub expected_mode: Option<String>,
+    pub owned_by_user: bool,
+    /// Lives outside the data dir (a configured `privateKeyPath` or `tokenPath`).
+    pub external: bool,
+    pub severity: Severity,
+    pub issues: Vec<String>,
+    /// Repair can fix every issue (mode only; it never changes ownership).
+    pub fixable: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditReport {
+    /// File modes are only checked on Unix.
+    pub supported: bool,
+    pub severity: Severity,
+    pub items: Vec<AuditItem>,
+}
+
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Key and token files referenced from config.json or .env that sit outside `dir`.
+fn external_secrets(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<String> = Vec::new();
+    if let Ok(raw) = std::fs::read_to_string(dir.join(crate::config::CONFIG_FILE)) {
+        if let Ok(cfg) = serde_json::from_str::<serde_json::Value>(&raw) {
+            paths.extend(cfg["wallet"]["privateKeyPath"].as_str().map(str::to_string));
+            paths.extend(cfg["telegram"]["tokenPath"].as_str().map(str::to_string));
+        }
+    }
+    if let Ok(env) = EnvFile::load(&dir.join(ENV_FILE)) {
+        paths.extend(["HL_PRIVATE_KEY_PATH", "TG_TOKEN_PATH"].iter().filter_map(|k| env.get(k)).map(str::to_string));
+    }
+    let canonical_dir = dunce::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let mut out: Vec<PathBuf> = paths
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| expand_home(p.trim()))
+        .filter(|p| p.exists())
+        .map(|p| dunce::canonicalize(&p).unwrap_or(p))
+        .filter(|p| !p.starts_with(&canonical_dir))
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Everything to audit that exists, with how sensitive it is.
+fn targets(dir: &Path) -> Vec<(PathBuf, Sensitivity, bool)> {
+    let mut targets = vec![(dir.to_path_buf(), Sensitivity::Directory, false)];
+    targets.extend(SECRET_FILES.iter().map(|f| (dir.join(f), Sensitivity::Secret, false)));
+    targets.extend(PRIVATE_FILES.iter().map(|f| (dir.join(f), Sensitivity::Private, false)));
+    for sub in PRIVATE_DIRS {
+        let sub_dir = dir.join(sub);
+        targets.push((sub_dir.clone(), Sensitivity::Directory, false));
+        let files = std::fs::read_dir(&sub_dir).into_iter().flatten().flatten().map(|e| e.path()).filter(|p| p.is_file());
+        targets.extend(files.map(|p| (p, Sensitivity::Private, false)));
+    }
+    targets.extend(external_secrets(dir).into_iter().map(|p| (p, Sensitivity::Secret, true)));
+    targets.retain(|(p, _, _)| p.exists());
+    targets
+}
+
+#[cfg(unix)]
+fn audit_item(path: PathBuf, sensitivity: Sensitivity, external: bool) -> AuditItem {
+    use std::os::unix::fs::MetadataExt;
+    let expected = sensitivity.expected_mode();
+    let meta = match std::fs::metadata(&path) {
+        Ok(m) => m,
+        Err(e) => {
+            return AuditItem {
+                issues: vec![format!("Cannot read permissions: {}", e)],
+                path,
+                sensitivity,
+                mode: None,
+                expected_mode: Some(format!("{:04o}", expected)),
+                owned_by_user: false,
+                external,
+                severity: Severity::Warning,
+                fixable: false,
+            }
+        }
+    };
+    let mode = meta.mode() & 0o777;
+    let owned_by_user = meta.uid() == unsafe { libc::geteuid() };
+
+    let mut issues = Vec::new();
+    let mut severity = Severity::Ok;
+    if !owned_by_user {
+        issues.push(format!("Owned by user id {}, not the current user", meta.uid()));
+        severity = Severity::Critical;
+    }
+    if mode & 0o077 != 0 {
+        let who = match (mode & 0o070 != 0, mode & 0o007 != 0) {
+            (true, true) => "group and others",
+            (true, false) => "group",
+            _ => "others",
+        };
+        issues.push(format!("Accessible by {} (mode {:04o})", who, mode));
+        // A readable secret or an enterable data dir exposes credentials
+        let exposes_secrets = sensitivity == Sensitivity::Secret || (sensitivity == Sensitivity::Directory && mode & 0o005 != 0);
+        severity = severity.max(if exposes_secrets || mode & 0o022 != 0 { Severity::Critical } else { Severity::Warning });
+    }
+
+    AuditItem {
+        path,
+        sensitivity,
+        mode: Some(format!("{:04o}", mode)),
+        expected_mode: Some(format!("{:04o}", expected)),
+        owned_by_user,
+        external,
+        fixable: owned_by_user,
+        severity,
+        issues,
+    }
+}
+
+#[cfg(not(unix))]
+fn audit_item(path: PathBuf, sensitivity: Sensitivity, external: bool) -> AuditItem {
+    AuditItem {
+        path,
+        sensitivity,
+        mode: None,
+        expected_mode: None,
+        owned_by_user: true,
+        external,
+        severity: Severity::Ok,
+        issues: Vec::new(),
+        fixable: true,
+    }
+}
+
+pub fn audit(dir: &Path) -> AuditReport {
+    let items: Vec<AuditItem> = targets(dir).into_iter().map(|(p, s, external)| audit_item(p, s, external)).collect();
+    AuditReport {
+        supported: cfg!(unix),
+        severity: items.iter().map(|i| i.severity).max().unwrap_or(Severity::Ok),
+        items,
+    }
+}
+
+/// Clear group and other access on every flagged item the user owns.
+#[cfg(unix)]
+fn repair(report: &AuditReport) -> Vec<String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut failures = Vec::new();
+    for item in report.items.iter().filter(|i| i.severity != Severity::Ok && i.owned_by_user) {
+        let Ok(meta) = std::fs::metadata(&item.path) else { continue };
+        let owner = meta.permissions().mode() & 0o700;
+        let mode = owner | item.sensitivity.expected_mode();
+        if let Err(e) = std::fs::set_permissions(&item.path, std::fs::Permissions::from_mode(mode)) {
+            failures.push(format!("{}: {}", item.path.display(), e));
+        } else {
+            log::info!("Tightened {} to {:04o}", item.path.display(), mode);
+        }
+    }
+    failures
+}
+
+#[cfg(not(unix))]
+fn repair(_report: &AuditReport) -> Vec<String> {
+    Vec::new()
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub fn security_audit() -> Result<AuditReport, String> {
+    Ok(audit(&crate::user_data_dir()?))
+}
+
+/// Tighten every flagged path the current user owns, then re-audit. Ownership
+/// problems remain in the returned report.
+#[tauri::command]
+pub fn repair_permissions(app: tauri::AppHandle) -> Result<AuditReport, String> {
+    let result = repair_all();
+    crate::audit_log::record(&app, "repair_permissions", serde_json::json!({}), result)
+}
+
+fn repair_all() -> Result<AuditReport, String> {
+    let dir = crate::user_data_dir()?;
+    let failures = repair(&audit(&dir));
+    if !failures.is_empty() {
+        return Err(format!("Could not fix: {}", failures.join("; ")));
+    }
+    Ok(audit(&dir))
+}
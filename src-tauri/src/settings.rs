@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::fsutil::{self, WriteOptions};
+
+// --- App Settings ---
+//
+// Preferences of the desktop app itself, kept apart from config.json so they never
+// reach the bot or its profiles.
+
+const SETTINGS_FILE: &str = "app-settings.json";
+
+/// What to do with a running bot when config.json changes on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReloadPolicy {
+    Ignore,
+    /// Tell the UI and let the user choose.
+    #[default]
+    Prompt,
+    Restart,
+    RestartWhenFlat,
+}
+
+/// Where and how the app reaches the license API.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LicenseSettings {
+    /// Full validate URLs, tried in order until one answers.
+    pub endpoints: Vec<String>,
+    pub timeout_secs: u64,
+    /// Extra attempts per endpoint after the first, with doubling delays.
+    pub retries: u32,
+    pub retry_delay_ms: u64,
+    /// HTTP(S) proxy for license requests. When unset, the HTTP_PROXY, HTTPS_PROXY
+    /// and NO_PROXY environment variables apply.
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that bypass `proxy`.
+    pub no_proxy: Option<String>,
+}
+
+pub const DEFAULT_LICENSE_ENDPOINTS: &[&str] =
+    &["https://c13studios.com/api/validate", "https://hlsignalbot.netlify.app/api/validate"];
+
+impl Default for LicenseSettings {
+    fn default() -> Self {
+        Self {
+            endpoints: DEFAULT_LICENSE_ENDPOINTS.iter().map(|s| s.to_string()).collect(),
+            timeout_secs: 5,
+            retries: 1,
+            retry_delay_ms: 500,
+            proxy: None,
+            no_proxy: None,
+        }
+    }
+}
+
+impl LicenseSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.endpoints.is_empty() {
+            return Err("At least one license endpoint is required".into());
+        }
+        for endpoint in &self.endpoints {
+            let url = reqwest::Url::parse(endpoint).map_err(|e| format!("Invalid license endpoint {}: {}", endpoint, e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("License endpoint {} must be http or https", endpoint));
+            }
+        }
+        if !(1..=60).contains(&self.timeout_secs) {
+            return Err("License timeout must be between 1 and 60 seconds".into());
+        }
+        if self.retries > 5 {
+            return Err("License retries must be 5 or fewer".into());
+        }
+        if self.retry_delay_ms > 30_000 {
+            return Err("License retry delay must be 30 seconds or less".into());
+        }
+        if let Some(proxy) = self.proxy.as_deref() {
+            reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy {}: {}", proxy, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub config_reload: ReloadPolicy,
+    pub license: LicenseSettings,
+}
+
+fn settings_path(dir: &Path) -> PathBuf {
+    dir.join(SETTINGS_FILE)
+}
+
+/// Saved settings, or the defaults when the file is missing or unreadable.
+pub fn load(dir: &Path) -> AppSettings {
+    let path = settings_path(dir);
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable {}: {}", SETTINGS_FILE, e);
+            AppSettings::default()
+        }),
+        Err(_) => AppSettings::default(),
+    }
+}
+
+fn save(dir: &Path, settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path(dir);
+    let text = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fsutil::write_file(&path, text, WriteOptions::BACKUP)
+}
+
+#[tauri::command]
+pub fn get_app_settings() -> Result<AppSettings, String> {
+    Ok(load(&crate::user_data_dir()?))
+}
+
+#[tauri::command]
+pub fn set_app_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<AppSettings, String> {
+    let mut params = serde_json::to_value(&settings).unwrap_or_default();
+    // A proxy URL can carry credentials
+    if settings.license.proxy.as_deref().is_some_and(|p| p.contains('@')) {
+        params["license"]["proxy"] = crate::audit_log::REDACTED.into();
+    }
+    let result = store(settings);
+    crate::audit_log::record(&app, "set_app_settings", params, result)
+}
+
+fn store(mut settings: AppSettings) -> Result<AppSettings, String> {
+    let license = &mut settings.license;
+    license.endpoints = license.endpoints.iter().map(|e| e.trim().to_string()).filter(|e| !e.is_empty()).collect();
+    for opt in [&mut license.proxy, &mut license.no_proxy] {
+        *opt = opt.take().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    }
+    license.validate()?;
+    save(&crate::user_data_dir()?, &settings)?;
+    Ok(settings)
+}
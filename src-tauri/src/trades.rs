@@ -0,0 +1,500 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+pub const TRADES_SOURCE: &str = "trades.jsonl";
+
+// --- Trade Records ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TradeAction {
+    Open,
+    Close,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Long,
+    Short,
+}
+
+/// One line of `trades.jsonl`, as written by index.mjs and close.mjs.
+/// Unknown keys are kept in `extra` so nothing the bot logs is lost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeRecord {
+    pub ts: String,
+    pub action: TradeAction,
+    #[serde(default)]
+    pub side: Option<Side>,
+    #[serde(default)]
+    pub size_btc: Option<f64>,
+    #[serde(default)]
+    pub entry_px: Option<f64>,
+    #[serde(default)]
+    pub exit_px: Option<f64>,
+    #[serde(default)]
+    pub notional_usd: Option<f64>,
+    #[serde(default)]
+    pub pnl_usd: Option<f64>,
+    #[serde(default)]
+    pub hl_pnl: Option<f64>,
+    #[serde(default)]
+    pub hl_fee: Option<f64>,
+    #[serde(default)]
+    pub leader: Option<String>,
+    #[serde(default)]
+    pub partial: Option<bool>,
+    #[serde(default)]
+    pub tp_index: Option<u32>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TradeAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TradeAction::Open => "OPEN",
+            TradeAction::Close => "CLOSE",
+            TradeAction::Other => "OTHER",
+        }
+    }
+}
+
+impl Side {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Side::Long => "long",
+            Side::Short => "short",
+        }
+    }
+}
+
+impl TradeRecord {
+    pub fn ts_ms(&self) -> Option<i64> {
+        chrono::DateTime::parse_from_rfc3339(&self.ts).ok().map(|d| d.timestamp_millis())
+    }
+
+    /// Why a CLOSE happened. Partial take-profits carry `tpIndex` (0-based) instead
+    /// of a `reason`, and close.mjs marks manual closes via `leader`.
+    pub fn exit_reason(&self) -> Option<String> {
+        if self.action != TradeAction::Close {
+            return None;
+        }
+        if let Some(r) = self.reason.as_deref().filter(|r| !r.is_empty()) {
+            return Some(r.to_string());
+        }
+        if let Some(i) = self.tp_index {
+            return Some(format!("tp{}", i + 1));
+        }
+        if self.leader.as_deref() == Some("manual_close") {
+            return Some("manual".into());
+        }
+        Some("unknown".into())
+    }
+}
+
+pub fn parse_line(line: &str) -> Option<TradeRecord> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    match serde_json::from_str::<TradeRecord>(line) {
+        Ok(rec) => Some(rec),
+        Err(e) => {
+            log::warn!("Skipping malformed trades.jsonl line ({}): {}", e, line);
+            None
+        }
+    }
+}
+
+pub fn trade_log_path(config_dir: &Path) -> PathBuf {
+    config_dir.join("trades.jsonl")
+}
+
+/// Every parseable record in `trades.jsonl`, in file order.
+pub fn load_trade_log(config_dir: &Path) -> Result<Vec<TradeRecord>, String> {
+    let path = trade_log_path(config_dir);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => Ok(text.lines().filter_map(parse_line).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+// --- Round Trips ---
+
+/// One position from OPEN to its final CLOSE, with partial take-profits folded in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundTrip {
+    /// None when the log has a CLOSE without a matching OPEN (e.g. a truncated log).
+    pub open_ts_ms: Option<i64>,
+    pub close_ts_ms: i64,
+    pub side: Option<Side>,
+    pub entry_px: Option<f64>,
+    pub notional_usd: Option<f64>,
+    pub pnl_usd: f64,
+    pub fees_usd: f64,
+    pub exit_reason: String,
+    pub closes: u32,
+}
+
+impl RoundTrip {
+    /// When the trade was taken — the close time for orphaned closes.
+    pub fn entry_ts_ms(&self) -> i64 {
+        self.open_ts_ms.unwrap_or(self.close_ts_ms)
+    }
+}
+
+/// Pair OPEN records with their CLOSEs. A CLOSE marked `partial: true` keeps the
+/// position open; any other CLOSE ends it. Positions still open at the end of the
+/// log are left out.
+pub fn round_trips(records: &[TradeRecord]) -> Vec<RoundTrip> {
+    let mut sorted: Vec<(i64, &TradeRecord)> = records.iter().filter_map(|r| r.ts_ms().map(|t| (t, r))).collect();
+    sorted.sort_by_key(|(t, _)| *t);
+
+    let mut trips = Vec::new();
+    let mut open: Option<RoundTrip> = None;
+    for (ts, rec) in sorted {
+        match rec.action {
+            TradeAction::Open => {
+                // A new entry while a position is still open means its final close was
+                // never logged; keep whatever it realised through partial closes.
+                if let Some(prev) = open.take().filter(|t| t.closes > 0) {
+                    trips.push(prev);
+                }
+                open = Some(RoundTrip {
+                    open_ts_ms: Some(ts),
+                    close_ts_ms: ts,
+                    side: rec.side,
+                    entry_px: rec.entry_px,
+                    notional_usd: rec.notional_usd.or(match (rec.entry_px, rec.size_btc) {
+                        (Some(px), Some(sz)) => Some(px * sz),
+                        _ => None,
+                    }),
+                    pnl_usd: 0.0,
+                    fees_usd: 0.0,
+                    exit_reason: String::new(),
+                    closes: 0,
+                });
+            }
+            TradeAction::Close => {
+                let mut trip = open.take().unwrap_or(RoundTrip {
+                    open_ts_ms: None,
+                    close_ts_ms: ts,
+                    side: rec.side,
+                    entry_px: rec.entry_px,
+                    notional_usd: None,
+                    pnl_usd: 0.0,
+                    fees_usd: 0.0,
+                    exit_reason: String::new(),
+                    closes: 0,
+                });
+                trip.close_ts_ms = ts;
+                trip.pnl_usd += rec.pnl_usd.unwrap_or(0.0);
+                trip.fees_usd += rec.hl_fee.unwrap_or(0.0);
+                trip.exit_reason = rec.exit_reason().unwrap_or_default();
+                trip.closes += 1;
+                if rec.partial == Some(true) {
+                    open = Some(trip);
+                } else {
+                    trips.push(trip);
+                }
+            }
+            TradeAction::Other => {}
+        }
+    }
+    trips
+}
+
+// --- Trade Log Tail ---
+
+/// Identifies the file behind a path so a rename-and-recreate rotation can be told
+/// apart from plain appends.
+#[cfg(unix)]
+fn file_identity(meta: &std::fs::Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (meta.dev(), meta.ino())
+}
+
+#[cfg(not(unix))]
+fn file_identity(meta: &std::fs::Metadata) -> (u64, u64) {
+    let created = meta
+        .created()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    (created, 0)
+}
+
+fn format_identity(id: (u64, u64)) -> String {
+    format!("{}:{}", id.0, id.1)
+}
+
+/// How many bytes before the read offset are hashed to recognise the file later.
+const FINGERPRINT_LEN: u64 = 256;
+
+/// Hash of the bytes just before `offset`, or None at the start of the file or when
+/// the file is now shorter than `offset`.
+fn fingerprint_at(file: &mut File, offset: u64) -> Option<String> {
+    if offset == 0 {
+        return None;
+    }
+    let start = offset.saturating_sub(FINGERPRINT_LEN);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut buf = vec![0; (offset - start) as usize];
+    file.read_exact(&mut buf).ok()?;
+    Some(crate::sha256_hex(&buf))
+}
+
+/// Where the tail has read up to, persisted between app runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TailPosition {
+    pub identity: Option<String>,
+    pub offset: u64,
+    /// Hash of the bytes just before `offset`, so a file truncated and rewritten past
+    /// the old offset isn't mistaken for one that was only appended to.
+    pub fingerprint: Option<String>,
+}
+
+/// Follows `trades.jsonl` across appends, truncation and rotation. Only complete
+/// (newline-terminated) lines are consumed; a half-written record is picked up on
+/// the next poll.
+pub struct TradeLogTail {
+    path: PathBuf,
+    file: Option<File>,
+    identity: Option<(u64, u64)>,
+    offset: u64,
+    fingerprint: Option<String>,
+}
+
+impl TradeLogTail {
+    /// Resume from a saved position. If the file at `path` is no longer the one the
+    /// position refers to, or its contents before the offset changed, start from the
+    /// beginning of the current file.
+    pub fn resume(path: PathBuf, position: &TailPosition) -> Self {
+        let mut tail = Self { path, file: None, identity: None, offset: 0, fingerprint: None };
+        if let Ok(mut file) = File::open(&tail.path) {
+            if let Ok(meta) = file.metadata() {
+                let id = file_identity(&meta);
+                if position.identity.as_deref() == Some(format_identity(id).as_str())
+                    && position.offset <= meta.len()
+                    && fingerprint_at(&mut file, position.offset) == position.fingerprint
+                {
+                    tail.offset = position.offset;
+                    tail.fingerprint = position.fingerprint.clone();
+                }
+                tail.identity = Some(id);
+                tail.file = Some(file);
+            }
+        }
+        tail
+    }
+
+    /// Current file identity, byte offset and fingerprint.
+    pub fn position(&self) -> TailPosition {
+        TailPosition {
+            identity: self.identity.map(format_identity),
+            offset: self.offset,
+            fingerprint: self.fingerprint.clone(),
+        }
+    }
+
+    pub fn poll(&mut self) -> Vec<TradeRecord> {
+        let mut out = Vec::new();
+
+        let current = std::fs::metadata(&self.path).ok().map(|m| file_identity(&m));
+        if current != self.identity {
+            // Rotated, deleted or newly created: drain whatever the old handle still
+            // has, then follow the new file from its start.
+            self.read_new_lines(&mut out);
+            self.file = File::open(&self.path).ok();
+            self.identity = match &self.file {
+                Some(f) => f.metadata().ok().map(|m| file_identity(&m)),
+                None => None,
+            };
+            self.offset = 0;
+            self.fingerprint = None;
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            // A shorter file was truncated; one whose bytes before the offset changed
+            // was truncated and has since grown past where we were.
+            let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            if len < self.offset || fingerprint_at(file, self.offset) != self.fingerprint {
+                log::info!("trades.jsonl was truncated — re-reading from start");
+                self.offset = 0;
+                self.fingerprint = None;
+            }
+        }
+
+        self.read_new_lines(&mut out);
+        out
+    }
+
+    fn read_new_lines(&mut self, out: &mut Vec<TradeRecord>) {
+        let Some(file) = self.file.as_mut() else { return };
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return;
+        }
+        let mut buf = Vec::new();
+        if file.read_to_end(&mut buf).is_err() {
+            return;
+        }
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else { return };
+        self.offset += end as u64 + 1;
+        self.fingerprint = fingerprint_at(file, self.offset);
+        for line in String::from_utf8_lossy(&buf[..=end]).lines() {
+            if let Some(rec) = parse_line(line) {
+                out.push(rec);
+            }
+        }
+    }
+}
+
+/// Poll `trades.jsonl` in the config dir, ingest new records into the local store and
+/// emit a `trade-appended` event per record. Runs for the whole app lifetime so fills
+/// from a bot started in CLI mode, or written by close.mjs, show up too.
+pub fn spawn_trade_watcher(app: tauri::AppHandle, config_dir: PathBuf) {
+    std::thread::spawn(move || {
+        let db = app.state::<crate::db::Db>();
+        let mut tail = TradeLogTail::resume(trade_log_path(&config_dir), &db.ingest_position(TRADES_SOURCE));
+
+        // Catch up on whatever was written while the app was closed, without replaying
+        // it to the UI as live events.
+        let backlog = tail.poll();
+        if let Err(e) = db.ingest_trades(&backlog, &tail.position()) {
+            log::warn!("Trade history ingest failed: {}", e);
+        }
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            let before = tail.position();
+            let records = tail.poll();
+            if records.is_empty() && tail.position() == before {
+                continue;
+            }
+            if let Err(e) = db.ingest_trades(&records, &tail.position()) {
+                log::warn!("Trade history ingest failed: {}", e);
+            }
+            for rec in &records {
+                let _ = app.emit("trade-appended", rec);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn log_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hl-signalbot-trades-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        trade_log_path(&dir)
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    fn line(ts: &str) -> String {
+        format!("{{\"ts\":\"{}\",\"action\":\"OPEN\"}}\n", ts)
+    }
+
+    fn append(path: &Path, text: &str) {
+        std::fs::OpenOptions::new().create(true).append(true).open(path).unwrap().write_all(text.as_bytes()).unwrap();
+    }
+
+    fn timestamps(records: &[TradeRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.ts.as_str()).collect()
+    }
+
+    #[test]
+    fn reads_appended_lines_once_and_waits_for_partial_ones() {
+        let path = log_path("append");
+        append(&path, &line("a"));
+        let mut tail = TradeLogTail::resume(path.clone(), &TailPosition::default());
+        assert_eq!(timestamps(&tail.poll()), ["a"]);
+        assert!(tail.poll().is_empty());
+
+        let next = line("b");
+        let (head, rest) = next.split_at(10);
+        append(&path, head);
+        assert!(tail.poll().is_empty());
+        append(&path, rest);
+        assert_eq!(timestamps(&tail.poll()), ["b"]);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn rereads_a_truncated_log() {
+        let path = log_path("truncate");
+        append(&path, &(line("a") + &line("b")));
+        let mut tail = TradeLogTail::resume(path.clone(), &TailPosition::default());
+        assert_eq!(tail.poll().len(), 2);
+
+        std::fs::write(&path, line("c")).unwrap();
+        assert_eq!(timestamps(&tail.poll()), ["c"]);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn rereads_a_log_truncated_and_regrown_past_the_offset() {
+        let path = log_path("regrow");
+        append(&path, &(line("a") + &line("b")));
+        let mut tail = TradeLogTail::resume(path.clone(), &TailPosition::default());
+        assert_eq!(tail.poll().len(), 2);
+
+        std::fs::write(&path, line("c") + &line("d") + &line("e")).unwrap();
+        assert_eq!(timestamps(&tail.poll()), ["c", "d", "e"]);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn follows_a_rotated_log() {
+        let path = log_path("rotate");
+        append(&path, &line("a"));
+        let mut tail = TradeLogTail::resume(path.clone(), &TailPosition::default());
+        assert_eq!(tail.poll().len(), 1);
+
+        // Written to the old file after the tail's last read but before the rotation.
+        append(&path, &line("b"));
+        std::fs::rename(&path, path.with_extension("jsonl.1")).unwrap();
+        append(&path, &line("c"));
+        assert_eq!(timestamps(&tail.poll()), ["b", "c"]);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn resumes_only_when_the_log_is_unchanged_up_to_the_offset() {
+        let path = log_path("resume");
+        append(&path, &(line("a") + &line("b")));
+        let mut tail = TradeLogTail::resume(path.clone(), &TailPosition::default());
+        tail.poll();
+        let saved = tail.position();
+
+        append(&path, &line("c"));
+        assert_eq!(timestamps(&TradeLogTail::resume(path.clone(), &saved).poll()), ["c"]);
+
+        // Rewritten in place while the app was closed, longer than before.
+        std::fs::write(&path, line("d") + &line("e") + &line("f")).unwrap();
+        assert_eq!(timestamps(&TradeLogTail::resume(path.clone(), &saved).poll()), ["d", "e", "f"]);
+
+        let legacy = TailPosition { fingerprint: None, ..saved };
+        assert_eq!(TradeLogTail::resume(path.clone(), &legacy).poll().len(), 3);
+        cleanup(&path);
+    }
+}
@@ -0,0 +1,249 @@
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+use tauri::Manager;
+use zeroize::Zeroizing;
+use crate::dotenv::{EnvFile, ENV_FILE};
+
+// --- Wallet Key Checks ---
+//
+// A Hyperliquid key either belongs to the trading account itself (the master wallet)
+// or is an API/agent wallet the account approved to sign for it. Pasting an agent key
+// next to the wrong account address only fails once the bot signs its first order, so
+// the key is checked against the configured address up front.
+
+const HL_INFO_URL: &str = "https://api.hyperliquid.xyz/info";
+
+/// A secp256k1 private key: 32 bytes of hex, optionally 0x-prefixed, in 1..n-1.
+pub fn parse_private_key(hex_key: &str) -> Result<k256::SecretKey, String> {
+    let hex_key = hex_key.trim();
+    let digits = hex_key.strip_prefix("0x").unwrap_or(hex_key);
+    if digits.len() != 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Private key must be 64 hex characters (optionally 0x-prefixed)".into());
+    }
+    let bytes = Zeroizing::new(hex::decode(digits).map_err(|e| e.to_string())?);
+    k256::SecretKey::from_slice(&bytes).map_err(|_| "Private key is outside the secp256k1 range".into())
+}
+
+/// EIP-55 mixed-case form of a 20-byte hex address.
+pub fn checksum_address(address: &str) -> String {
+    let lower = address.trim_start_matches("0x").to_ascii_lowercase();
+    let hash = Keccak256::digest(lower.as_bytes());
+    let mixed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    format!("0x{}", mixed)
+}
+
+/// A 0x-prefixed 40 hex character address, returned in checksummed form. Mixed-case
+/// input must carry a valid EIP-55 checksum, which catches most typos.
+pub fn parse_address(address: &str) -> Result<String, String> {
+    let address = address.trim();
+    let digits = address.strip_prefix("0x").ok_or("Address must start with 0x")?;
+    if digits.len() != 40 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Address must be 0x followed by 40 hex characters".into());
+    }
+    let checksummed = checksum_address(digits);
+    let mixed_case = digits.chars().any(|c| c.is_ascii_uppercase()) && digits.chars().any(|c| c.is_ascii_lowercase());
+    if mixed_case && checksummed != address {
+        return Err("Address checksum doesn't match; check for a typo".into());
+    }
+    Ok(checksummed)
+}
+
+/// Ethereum address of `key`: the last 20 bytes of keccak256 of the uncompressed public key.
+pub fn address_of(key: &k256::SecretKey) -> String {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    let point = key.public_key().to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+    checksum_address(&hex::encode(&hash[12..]))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WalletKind {
+    /// The key is the configured account's own key.
+    Master,
+    /// A different wallet, which only works if the account approved it as an API wallet.
+    Agent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyCheck {
+    pub derived_address: String,
+    pub configured_address: Option<String>,
+    /// Where the configured address came from: `argument`, `env` or `config`.
+    pub address_source: Option<&'static str>,
+    pub kind: Option<WalletKind>,
+    /// For agent keys: whether Hyperliquid lists it as approved for the account.
+    /// `None` when not checked or Hyperliquid couldn't be reached.
+    pub agent_approved: Option<bool>,
+    pub agent_name: Option<String>,
+    pub message: String,
+}
+
+/// The address the bot will trade for: HL_WALLET_ADDRESS overrides config.json.
+fn configured_address(dir: &std::path::Path) -> Option<(String, &'static str)> {
+    let env = EnvFile::load(&dir.join(ENV_FILE)).ok()?;
+    if let Some(addr) = env.get("HL_WALLET_ADDRESS").filter(|a| !a.trim().is_empty()) {
+        return Some((addr.trim().to_string(), "env"));
+    }
+    let raw = std::fs::read_to_string(dir.join(crate::config::CONFIG_FILE)).ok()?;
+    let cfg: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    cfg["wallet"]["address"].as_str().filter(|a| !a.trim().is_empty()).map(|a| (a.trim().to_string(), "config"))
+}
+
+/// Look `agent` up in the account's approved API wallets. `None` if the request fails.
+async fn approved_agent(account: &str, agent: &str) -> Option<(bool, Option<String>)> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .unwrap_or_default();
+    let resp = client
+        .post(HL_INFO_URL)
+        .json(&serde_json::json!({ "type": "extraAgents", "user": account.to_ascii_lowercase() }))
+        .send()
+        .await
+        .ok()?;
+    let agents: Vec<serde_json::Value> = resp.json().await.ok()?;
+    let found = agents
+        .iter()
+        .find(|a| a["address"].as_str().is_some_and(|addr| addr.eq_ignore_ascii_case(agent)));
+    Some((found.is_some(), found.and_then(|a| a["name"].as_str()).map(str::to_string)))
+}
+
+/// Validate a private key and match it against the wallet address the bot will use.
+/// `private_key` defaults to the unlocked key store, `address` to HL_WALLET_ADDRESS
+/// or `wallet.address`. With `check_agent`, an agent key is looked up on Hyperliquid.
+#[tauri::command]
+pub async fn check_private_key(
+    app: tauri::AppHandle,
+    private_key: Option<String>,
+    address: Option<String>,
+    check_agent: Option<bool>,
+) -> Result<KeyCheck, String> {
+    let key = match private_key.map(Zeroizing::new) {
+        Some(k) => k,
+        None => app.state::<crate::keystore::Keystore>().key().ok_or("No key given and the key store is locked")?,
+    };
+    let derived_address = address_of(&parse_private_key(&key)?);
+
+    let configured = match address.filter(|a| !a.trim().is_empty()) {
+        Some(a) => Some((a, "argument")),
+        None => configured_address(&crate::bot_config_dir(&app)?),
+    };
+    let Some((configured, source)) = configured else {
+        return Ok(KeyCheck {
+            message: format!("Valid key for {}. No wallet address is configured to compare it with.", derived_address),
+            derived_address,
+            configured_address: None,
+            address_source: None,
+            kind: None,
+            agent_approved: None,
+            agent_name: None,
+        });
+    };
+    let configured = parse_address(&configured).map_err(|e| format!("Configured wallet address: {}", e))?;
+
+    if configured == derived_address {
+        return Ok(KeyCheck {
+            message: format!("This is the master key for {}.", configured),
+            derived_address,
+            configured_address: Some(configured),
+            address_source: Some(source),
+            kind: Some(WalletKind::Master),
+            agent_approved: None,
+            agent_name: None,
+        });
+    }
+
+    let lookup = if check_agent.unwrap_or(false) { approved_agent(&configured, &derived_address).await } else { None };
+    let message = match &lookup {
+        Some((true, name)) => format!(
+            "This is API wallet {}{}, approved to trade for {}.",
+            derived_address,
+            name.as_deref().map(|n| format!(" ('{}')", n)).unwrap_or_default(),
+            configured
+        ),
+        Some((false, _)) => format!(
+            "This key belongs to {}, which is not {} and not an API wallet approved for it. Check that the key and address are from the same account.",
+            derived_address, configured
+        ),
+        None => format!(
+            "This key belongs to {}, not {}. That only works if it is an API wallet approved for {} on Hyperliquid.",
+            derived_address, configured, configured
+        ),
+    };
+    Ok(KeyCheck {
+        message,
+        derived_address,
+        configured_address: Some(configured),
+        address_source: Some(source),
+        kind: Some(WalletKind::Agent),
+        agent_approved: lookup.as_ref().map(|(approved, _)| *approved),
+        agent_name: lookup.and_then(|(_, name)| name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vectors from EIP-55.
+    const EIP55: &[&str] = &[
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn checksums_the_eip55_vectors() {
+        for address in EIP55 {
+            assert_eq!(checksum_address(&address.to_ascii_lowercase()), *address);
+            assert_eq!(parse_address(address).unwrap(), *address);
+            assert_eq!(parse_address(&address.to_ascii_lowercase()).unwrap(), *address);
+        }
+    }
+
+    #[test]
+    fn rejects_a_bad_checksum() {
+        let err = parse_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").unwrap_err();
+        assert_eq!(err, "Address checksum doesn't match; check for a typo");
+        assert!(parse_address("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(parse_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+    }
+
+    #[test]
+    fn derives_known_addresses() {
+        let pairs = [
+            ("0x0000000000000000000000000000000000000000000000000000000000000001", "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"),
+            ("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318", "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"),
+        ];
+        for (key, address) in pairs {
+            assert_eq!(address_of(&parse_private_key(key).unwrap()), address);
+        }
+    }
+
+    #[test]
+    fn rejects_keys_outside_the_curve_order() {
+        let out_of_range = "Private key is outside the secp256k1 range";
+        let zero = "0".repeat(64);
+        assert_eq!(parse_private_key(&zero).unwrap_err(), out_of_range);
+        let n = "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141";
+        assert_eq!(parse_private_key(n).unwrap_err(), out_of_range);
+        assert_eq!(parse_private_key(&"f".repeat(64)).unwrap_err(), out_of_range);
+        let n_minus_1 = "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364140";
+        assert!(parse_private_key(n_minus_1).is_ok());
+        assert!(parse_private_key("0x1234").is_err());
+    }
+}
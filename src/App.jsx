@@ -5,6 +5,8 @@ import Setup from './pages/Setup';
 import Dashboard from './pages/Dashboard';
 import TradeLog from './pages/TradeLog';
 import Settings from './pages/Settings';
+import { onConfigChanged, applyConfigChange } from './lib/bot';
+import { validateLicense, getLicenseStatus, onLicenseStatus } from './lib/license';
 import { version as APP_VERSION } from '../package.json';
 
 export default function App() {
@@ -13,6 +15,10 @@ export default function App() {
   const [nodeOk, setNodeOk] = useState(null);
   const [nodeError, setNodeError] = useState('');
   const [page, setPage] = useState('dashboard');
+  const [configChange, setConfigChange] = useState(null);
+  const [configChangeError, setConfigChangeError] = useState('');
+  const [license, setLicense] = useState(null);
+  const [licenseChecking, setLicenseChecking] = useState(false);
 
   useEffect(() => {
     if (!licensed) return;
@@ -38,6 +44,54 @@ export default function App() {
     });
   }, []);
 
+  useEffect(() => {
+    if (!licensed) return;
+    (async () => {
+      let status = await getLicenseStatus();
+      if (status.state === 'unlicensed') {
+        // Activated before the app kept license.json: register the key with the backend
+        const result = await validateLicense(localStorage.getItem('license_key'));
+        if (result.state === 'invalid') {
+          localStorage.removeItem('license_key');
+          setLicensed(false);
+          return;
+        }
+        status = await getLicenseStatus();
+      }
+      setLicense(status);
+    })().catch(() => {});
+    return onLicenseStatus(setLicense);
+  }, [licensed]);
+
+  async function handleRecheckLicense() {
+    setLicenseChecking(true);
+    try {
+      await validateLicense(localStorage.getItem('license_key') || '');
+      setLicense(await getLicenseStatus());
+    } catch {}
+    setLicenseChecking(false);
+  }
+
+  function handleEnterLicense() {
+    localStorage.removeItem('license_key');
+    setLicense(null);
+    setLicensed(false);
+  }
+
+  useEffect(() => onConfigChanged(change => {
+    setConfigChangeError(change.error || '');
+    setConfigChange(change.action === 'prompt' || change.action === 'rejected' || change.action === 'restartFailed' ? change : null);
+  }), []);
+
+  async function handleApplyConfig(mode) {
+    try {
+      await applyConfigChange(mode);
+      setConfigChange(null);
+    } catch (e) {
+      setConfigChangeError(typeof e === 'string' ? e : e?.message || 'Failed to apply config.');
+    }
+  }
+
   if (!licensed) {
     return <License onActivate={() => setLicensed(true)} />;
   }
@@ -91,6 +145,41 @@ export default function App() {
         </div>
       </nav>
       <main className="main">
+        {license && license.state !== 'valid' && (
+          <div className="card" style={{ marginBottom: 16, display: 'flex', alignItems: 'center', gap: 12, flexWrap: 'wrap' }}>
+            <span className={license.botAllowed ? 'warning-msg' : 'error-msg'} style={{ flex: 1, marginTop: 0 }}>
+              {license.state === 'grace' && <strong>License not verified. </strong>}
+              {license.state === 'revoked' && <strong>License revoked. </strong>}
+              {license.state === 'expired' && <strong>License expired. </strong>}
+              {license.reason}
+            </span>
+            {license.state === 'unlicensed' || license.state === 'invalid' ? (
+              <button className="btn btn-primary" onClick={handleEnterLicense}>Enter license key</button>
+            ) : (
+              <button className="btn btn-outline" onClick={handleRecheckLicense} disabled={licenseChecking}>
+                {licenseChecking ? 'Checking...' : 'Check now'}
+              </button>
+            )}
+          </div>
+        )}
+        {configChange && (
+          <div className="card" style={{ marginBottom: 16, display: 'flex', alignItems: 'center', gap: 12, flexWrap: 'wrap' }}>
+            {configChange.valid ? (
+              <>
+                <span style={{ flex: 1, fontSize: 13 }}>config.json changed. Restart the bot to apply it.</span>
+                <button className="btn btn-primary" onClick={() => handleApplyConfig('now')}>Restart now</button>
+                <button className="btn btn-outline" onClick={() => handleApplyConfig('whenFlat')}>Restart when flat</button>
+              </>
+            ) : (
+              <span className="warning-msg" style={{ flex: 1, marginTop: 0 }}>
+                config.json was edited but is invalid, so the bot keeps running on its previous settings:{' '}
+                {configChange.errors.map(e => `${e.field || 'config'}: ${e.message}`).join('; ')}
+              </span>
+            )}
+            <button className="btn btn-outline" onClick={() => setConfigChange(null)}>Dismiss</button>
+            {configChangeError && <div className="error-msg" style={{ width: '100%' }}>{configChangeError}</div>}
+          </div>
+        )}
         <div style={{ display: page === 'dashboard' ? 'block' : 'none' }}><Dashboard /></div>
         <div style={{ display: page === 'tradelog' ? 'block' : 'none' }}><TradeLog /></div>
         <div style={{ display: page === 'settings' ? 'block' : 'none' }}><Settings /></div>
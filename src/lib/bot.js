@@ -26,6 +26,16 @@ export function onBotEvent(callback) {
   };
 }
 
+// config.json changed on disk; see config_watch.rs for the payload
+export function onConfigChanged(callback) {
+  const pending = listen('config-changed', (event) => callback(event.payload));
+  return () => { pending.then(fn => fn()); };
+}
+
+export async function applyConfigChange(mode) {
+  return await invoke('apply_config_change', { mode });
+}
+
 export async function startBot() {
   try {
     await invoke('start_bot');
@@ -10,7 +10,7 @@ export async function readConfig() {
 }
 
 export async function writeConfig(config) {
-  await invoke('write_bot_file', { filename: 'config.json', contents: JSON.stringify(config, null, 2) });
+  return await invoke('write_config', { config });
 }
 
 export async function configExists() {
@@ -21,17 +21,27 @@ export async function configExists() {
   }
 }
 
-export async function writeEnv(content) {
-  await invoke('write_bot_file', { filename: '.env', contents: content });
+export async function getEnv() {
+  return invoke('get_env');
 }
 
-export async function readTradeLog() {
+export async function updateEnv(set = {}, unset = []) {
+  await invoke('update_env', { set, unset });
+}
+
+export async function readTradeLog(query = {}) {
   try {
-    const text = await invoke('read_bot_file', { filename: 'trades.jsonl' });
-    return text.trim().split('\n').filter(Boolean).map(line => {
-      try { return JSON.parse(line); } catch { return null; }
-    }).filter(Boolean).reverse();
+    return await invoke('query_trades', { query });
   } catch {
     return [];
   }
 }
+
+// Aggregates over the local trade store (trades.jsonl ingested by db.rs)
+export async function getTradeSummary(from, to) {
+  return await invoke('trade_summary', { from, to });
+}
+
+export async function getPnlByExitReason(from, to) {
+  return await invoke('pnl_by_exit_reason', { from, to });
+}
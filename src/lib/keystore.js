@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// Encrypted private key storage; see keystore.rs
+
+export async function keystoreStatus() {
+  try {
+    return await invoke('keystore_status');
+  } catch {
+    return { exists: false, unlocked: false, outdated: false, plaintextKey: false };
+  }
+}
+
+// Re-running setup replaces an existing key store, hence `overwrite`
+export async function createKeystore(passphrase, privateKey = null, overwrite = false) {
+  await invoke('create_keystore', { privateKey, passphrase, overwrite });
+}
+
+export async function unlockKeystore(passphrase) {
+  await invoke('unlock_keystore', { passphrase });
+}
+
+export async function lockKeystore() {
+  await invoke('lock_keystore');
+}
+
+// Going back to a key in .env; the bot would otherwise keep getting the stored key
+export async function deleteKeystore() {
+  await invoke('delete_keystore');
+}
+
+export async function changeKeystorePassphrase(current, next) {
+  await invoke('change_keystore_passphrase', { current, new: next });
+}
+
+export async function reencryptKeystore(passphrase) {
+  await invoke('reencrypt_keystore', { passphrase });
+}
@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+// License checks and background status; see license.rs
+
+export async function validateLicense(key) {
+  return await invoke('validate_license', { key });
+}
+
+export async function getLicenseStatus() {
+  return await invoke('get_license_status');
+}
+
+// Emitted by the license monitor whenever the state or grace deadline changes
+export function onLicenseStatus(callback) {
+  const pending = listen('license-status', (event) => callback(event.payload));
+  return () => { pending.then(fn => fn()); };
+}
@@ -1,6 +1,9 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { startBot, stopBot, onBotEvent, isBotRunning } from '../lib/bot';
+import { keystoreStatus, unlockKeystore } from '../lib/keystore';
+import { getTradeSummary } from '../lib/config';
 
 export default function Dashboard() {
   const [running, setRunning] = useState(false);
@@ -18,6 +21,9 @@ export default function Dashboard() {
   const [confirmClose, setConfirmClose] = useState(false);
   const [equity, setEquity] = useState(null);
   const [syncing, setSyncing] = useState(false);
+  const [keystoreLocked, setKeystoreLocked] = useState(false);
+  const [weekSummary, setWeekSummary] = useState(null);
+  const [passphrase, setPassphrase] = useState('');
 
   // Sync with actual bot state on mount
   useEffect(() => {
@@ -27,6 +33,15 @@ export default function Dashboard() {
         setStatus('running');
       }
     });
+    keystoreStatus().then(s => setKeystoreLocked(s.exists && !s.unlocked));
+  }, []);
+
+  // Last 7 days from the local trade store, refreshed as the bot records trades
+  useEffect(() => {
+    const load = () => getTradeSummary(Date.now() - 7 * 24 * 60 * 60 * 1000).then(setWeekSummary).catch(() => {});
+    load();
+    const unlisten = listen('trade-appended', load);
+    return () => { unlisten.then(fn => fn()); };
   }, []);
 
   // Poll health every 5 seconds
@@ -97,6 +112,24 @@ export default function Dashboard() {
     if (ok) { setRunning(true); setStatus('running'); }
   }, []);
 
+  const handleUnlockAndStart = useCallback(async (e) => {
+    e.preventDefault();
+    setStarting(true);
+    setLastError(null);
+    try {
+      await unlockKeystore(passphrase);
+      setPassphrase('');
+      setKeystoreLocked(false);
+    } catch (err) {
+      setLastError(typeof err === 'string' ? err : err?.message || 'Unlock failed');
+      setStarting(false);
+      return;
+    }
+    const ok = await startBot();
+    setStarting(false);
+    if (ok) { setRunning(true); setStatus('running'); }
+  }, [passphrase]);
+
   const handleStop = useCallback(async () => {
     await stopBot();
     setRunning(false);
@@ -194,6 +227,13 @@ export default function Dashboard() {
               </button>
               <button className="btn btn-danger" onClick={handleStop}>Stop</button>
             </>
+          ) : keystoreLocked ? (
+            <form onSubmit={handleUnlockAndStart} style={{ display: 'flex', gap: 8 }}>
+              <input className="form-input" type="password" placeholder="Key store passphrase" value={passphrase} onChange={e => setPassphrase(e.target.value)} style={{ width: 220 }} />
+              <button type="submit" className="btn btn-primary" disabled={starting || !passphrase}>
+                {starting ? 'Unlocking...' : 'Unlock & Start'}
+              </button>
+            </form>
           ) : (
             <button className="btn btn-primary" onClick={handleStart} disabled={starting}>
               {starting ? 'Starting...' : 'Start Bot'}
@@ -295,6 +335,12 @@ export default function Dashboard() {
               Fees paid today: -${dailyFees.toFixed(2)}
             </div>
           )}
+          {weekSummary?.closes > 0 && (
+            <div className="text-muted" style={{ fontSize: 12, marginTop: 4 }}>
+              Last 7 days: {weekSummary.pnlUsd >= 0 ? '+' : ''}${weekSummary.pnlUsd.toFixed(2)} over {weekSummary.closes} closes
+              {weekSummary.winRate !== null && ` (${Math.round(weekSummary.winRate * 100)}% won)`}
+            </div>
+          )}
         </div>
         <div className="card">
           <div className="card-title">Health</div>
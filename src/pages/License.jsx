@@ -1,10 +1,11 @@
 import { useState } from 'react';
-import { invoke } from '@tauri-apps/api/core';
+import { validateLicense } from '../lib/license';
 
 export default function License({ onActivate }) {
   const [key, setKey] = useState('');
   const [error, setError] = useState('');
   const [loading, setLoading] = useState(false);
+  const [grace, setGrace] = useState(null);
 
   async function handleActivate() {
     if (!key.trim()) {
@@ -12,14 +13,16 @@ export default function License({ onActivate }) {
       return;
     }
     setError('');
+    setGrace(null);
     setLoading(true);
     try {
-      const valid = await invoke('validate_license', { key: key.trim() });
-      if (valid) {
-        localStorage.setItem('license_key', key.trim());
-        onActivate();
+      const result = await validateLicense(key.trim());
+      if (result.state === 'valid') {
+        activate();
+      } else if (result.state === 'grace') {
+        setGrace(result);
       } else {
-        setError('Invalid license key. Please check and try again.');
+        setError(result.reason || 'Invalid license key. Please check and try again.');
       }
     } catch (e) {
       setError(e?.message || 'Verification failed. Check your connection.');
@@ -27,6 +30,11 @@ export default function License({ onActivate }) {
     setLoading(false);
   }
 
+  function activate() {
+    localStorage.setItem('license_key', key.trim());
+    onActivate();
+  }
+
   return (
     <div className="license-page">
       <div className="license-box">
@@ -44,6 +52,15 @@ export default function License({ onActivate }) {
           />
         </div>
         {error && <div className="error-msg">{error}</div>}
+        {grace && (
+          <div className="warning-msg" style={{ marginTop: 12, textAlign: 'left' }}>
+            <strong>Your license could not be verified.</strong> {grace.reason} You can keep using the app offline until
+            {' '}{new Date(grace.graceUntil).toLocaleString()}; it needs to reach the license server before then.
+            <button className="btn btn-outline" onClick={activate} style={{ width: '100%', marginTop: 8, justifyContent: 'center' }}>
+              Continue offline
+            </button>
+          </div>
+        )}
         <button className="btn btn-primary btn-lg" onClick={handleActivate} disabled={loading} style={{ width: '100%', marginTop: 12, justifyContent: 'center' }}>
           {loading ? 'Verifying...' : 'Activate'}
         </button>
@@ -1,6 +1,13 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { readConfig, writeConfig } from '../lib/config';
+import { keystoreStatus, createKeystore, lockKeystore, changeKeystorePassphrase, reencryptKeystore } from '../lib/keystore';
+import { validateLicense } from '../lib/license';
+
+// License settings as form fields: endpoints one per line, empty strings for unset
+function licenseForm(license) {
+  return { ...license, endpoints: license.endpoints.join('\n'), proxy: license.proxy || '', noProxy: license.noProxy || '' };
+}
 
 function Tip({ text }) {
   return (
@@ -19,10 +26,36 @@ export default function Settings() {
   const [hasEdits, setHasEdits] = useState(false);
   const [error, setError] = useState('');
   const [showAdvanced, setShowAdvanced] = useState(false);
+  const [reloadPolicy, setReloadPolicy] = useState('prompt');
+
+  // License server settings
+  const [licenseNet, setLicenseNet] = useState(null);
+  const [licBusy, setLicBusy] = useState(false);
+  const [licMsg, setLicMsg] = useState('');
+  const [licError, setLicError] = useState('');
+
+  // Key store state
+  const [keystore, setKeystore] = useState(null);
+  const [ksCurrent, setKsCurrent] = useState('');
+  const [ksNew, setKsNew] = useState('');
+  const [ksBusy, setKsBusy] = useState(false);
+  const [ksMsg, setKsMsg] = useState('');
+  const [ksError, setKsError] = useState('');
+
+  // File permission audit
+  const [audit, setAudit] = useState(null);
+  const [auditBusy, setAuditBusy] = useState(false);
+  const [auditError, setAuditError] = useState('');
+
+  // Audit log of privileged actions
+  const [auditLog, setAuditLog] = useState(null);
+  const [auditChain, setAuditChain] = useState(null);
+  const [auditLogError, setAuditLogError] = useState('');
 
   // Telegram state
   const [tgEnabled, setTgEnabled] = useState(false);
   const [tgToken, setTgToken] = useState('');
+  const [tgTokenSaved, setTgTokenSaved] = useState(false);
   const [tgChat, setTgChat] = useState('');
   const [showToken, setShowToken] = useState(false);
   const [tgSaving, setTgSaving] = useState(false);
@@ -33,6 +66,11 @@ export default function Settings() {
   useEffect(() => { loadAll(); }, []);
 
   async function loadAll() {
+    invoke('get_app_settings').then(s => {
+      setReloadPolicy(s.configReload);
+      setLicenseNet(licenseForm(s.license));
+    }).catch(() => {});
+    keystoreStatus().then(setKeystore);
     const cfg = await readConfig();
     if (cfg) {
       setConfig(cfg);
@@ -54,17 +92,14 @@ export default function Settings() {
         }
       } catch {}
     }
-    // Try to read the saved token file
+    // The saved token is write-only; only whether one exists comes back
     try {
-      const storedToken = await invoke('read_bot_file', { filename: 'tg_token' });
-      if (storedToken && storedToken.trim()) setTgToken(storedToken.trim());
+      setTgTokenSaved(await invoke('bot_file_exists', { filename: 'tg_token' }));
     } catch {}
     // Fallback: read chat from .env if not in config
     try {
-      const envContents = await invoke('read_bot_file', { filename: '.env' });
-      for (const line of envContents.split('\n')) {
-        if (line.startsWith('TG_CHAT=') && !tgChat) setTgChat(line.split('=')[1]?.trim());
-      }
+      const chat = await invoke('get_env_var', { key: 'TG_CHAT' });
+      if (chat?.value && !tgChat) setTgChat(chat.value.trim());
     } catch {}
     setLoading(false);
   }
@@ -108,11 +143,46 @@ export default function Settings() {
     const saf = c?.setAndForget;
     if (saf?.enabled) {
       const safLev = Number(saf.leverage);
-      if (!Number.isFinite(safLev) || safLev < 1 || safLev > 125) errs.push('Set & Forget leverage must be between 1 and 125');
+      if (!Number.isFinite(safLev) || safLev < 1 || safLev > 50) errs.push('Set & Forget leverage must be between 1 and 50');
     }
     return errs;
   }
 
+  async function handleReloadPolicy(policy) {
+    setReloadPolicy(policy);
+    try {
+      const current = await invoke('get_app_settings');
+      await invoke('set_app_settings', { settings: { ...current, configReload: policy } });
+    } catch (e) {
+      setError(typeof e === 'string' ? e : (e?.message || 'Failed to save app settings.'));
+    }
+  }
+
+  async function handleSaveLicenseNet() {
+    setLicBusy(true);
+    setLicMsg('');
+    setLicError('');
+    try {
+      const current = await invoke('get_app_settings');
+      const license = {
+        endpoints: licenseNet.endpoints.split('\n'),
+        timeoutSecs: Number(licenseNet.timeoutSecs),
+        retries: Number(licenseNet.retries),
+        retryDelayMs: Number(licenseNet.retryDelayMs),
+        proxy: licenseNet.proxy || null,
+        noProxy: licenseNet.noProxy || null,
+      };
+      const saved = await invoke('set_app_settings', { settings: { ...current, license } });
+      setLicenseNet(licenseForm(saved.license));
+      const result = await validateLicense(localStorage.getItem('license_key') || '');
+      if (result.state === 'valid') setLicMsg('Saved. License verified.');
+      else setLicError(`Saved, but the license check failed: ${result.reason}`);
+    } catch (e) {
+      setLicError(typeof e === 'string' ? e : (e?.message || 'Failed to save license settings.'));
+    }
+    setLicBusy(false);
+  }
+
   async function handleSaveConfig() {
     setError('');
     const errs = validateConfig(config);
@@ -122,10 +192,11 @@ export default function Settings() {
       localStorage.setItem('bot_config', JSON.stringify(config));
       setSaved(true);
       setHasEdits(false);
-      setShowRestartNotice(true);
+      // Other policies are applied by the backend after the write
+      if (reloadPolicy === 'ignore') setShowRestartNotice(true);
       setTimeout(() => setSaved(false), 3000);
     } catch (e) {
-      setError(e?.message || 'Failed to save.');
+      setError(typeof e === 'string' ? e : (e?.message || 'Failed to save.'));
     }
   }
 
@@ -137,21 +208,59 @@ export default function Settings() {
   }
 
   async function handleTgTest() {
-    if (!tgToken.trim() || !tgChat.trim()) {
+    if ((!tgToken.trim() && !tgTokenSaved) || !tgChat.trim()) {
       setTgTestMsg('Enter both token and chat ID first.');
       return;
     }
     setTgTestMsg('Sending...');
     try {
-      const res = await fetch(`https://api.telegram.org/bot${tgToken.trim()}/sendMessage`, {
-        method: 'POST',
-        headers: { 'content-type': 'application/json' },
-        body: JSON.stringify({ chat_id: tgChat.trim(), text: 'HL Signalbot: test ping', disable_web_page_preview: true }),
-      });
-      const data = await res.json();
-      setTgTestMsg(data.ok ? 'Message sent! Check your Telegram.' : `Error: ${data.description || 'unknown'}`);
+      // An empty field tests the saved token
+      await invoke('send_telegram_test', { chat: tgChat.trim(), token: tgToken.trim() || null });
+      setTgTestMsg('Message sent! Check your Telegram.');
+    } catch (e) {
+      setTgTestMsg(typeof e === 'string' ? e : `Failed: ${e?.message || e}`);
+    }
+  }
+
+  async function handleKeystore(action, message) {
+    setKsBusy(true);
+    setKsMsg('');
+    setKsError('');
+    try {
+      await action();
+      setKsCurrent('');
+      setKsNew('');
+      setKsMsg(message);
+      setTimeout(() => setKsMsg(''), 3000);
+    } catch (e) {
+      setKsError(typeof e === 'string' ? e : e?.message || 'Key store update failed');
+    }
+    setKeystore(await keystoreStatus());
+    setKsBusy(false);
+  }
+
+  async function runAudit(repair) {
+    setAuditBusy(true);
+    setAuditError('');
+    try {
+      setAudit(await invoke(repair ? 'repair_permissions' : 'security_audit'));
     } catch (e) {
-      setTgTestMsg(`Failed: ${e?.message || e}`);
+      setAuditError(typeof e === 'string' ? e : e?.message || 'Audit failed');
+    }
+    setAuditBusy(false);
+  }
+
+  async function loadAuditLog() {
+    setAuditLogError('');
+    try {
+      const [entries, chain] = await Promise.all([
+        invoke('query_audit_log', { query: { limit: 50 } }),
+        invoke('verify_audit_log'),
+      ]);
+      setAuditLog(entries);
+      setAuditChain(chain);
+    } catch (e) {
+      setAuditLogError(typeof e === 'string' ? e : e?.message || 'Failed to read the audit log');
     }
   }
 
@@ -163,6 +272,15 @@ export default function Settings() {
       const configDir = await invoke('get_config_dir');
       const tgTokenPath = `${configDir}/tg_token`;
 
+      // .env goes first: it is validated, and a rejected value leaves everything unchanged
+      const envSet = { TG_ENABLED: String(tgEnabled) };
+      const envUnset = ['TG_TOKEN'];
+      if (tgEnabled && tgChat.trim()) envSet.TG_CHAT = tgChat.trim();
+      else envUnset.push('TG_CHAT');
+      if (tgEnabled) envSet.TG_TOKEN_PATH = tgTokenPath;
+      else envUnset.push('TG_TOKEN_PATH');
+      await invoke('update_env', { set: envSet, unset: envUnset });
+
       const cfg = await readConfig();
       if (!cfg.telegram) cfg.telegram = {};
       cfg.telegram.enabled = tgEnabled;
@@ -173,29 +291,15 @@ export default function Settings() {
 
       if (tgToken.trim()) {
         await invoke('write_secret_file', { path: tgTokenPath, contents: tgToken.trim() + '\n' });
+        setTgTokenSaved(true);
+        setTgToken('');
       }
 
-      try {
-        const envContents = await invoke('read_bot_file', { filename: '.env' });
-        const lines = envContents.split('\n');
-        const filtered = lines.filter(l =>
-          !l.startsWith('TG_ENABLED=') &&
-          !l.startsWith('TG_CHAT=') &&
-          !l.startsWith('TG_TOKEN_PATH=') &&
-          !l.startsWith('TG_TOKEN=')
-        );
-        filtered.push('', `TG_ENABLED=${tgEnabled}`);
-        if (tgEnabled && tgChat.trim()) filtered.push(`TG_CHAT=${tgChat.trim()}`);
-        if (tgEnabled) filtered.push(`TG_TOKEN_PATH=${tgTokenPath}`);
-        filtered.push('');
-        await invoke('write_bot_file', { filename: '.env', contents: filtered.join('\n') });
-      } catch {}
-
       localStorage.setItem('bot_config', JSON.stringify(cfg));
       setTgSaved(true);
       setTimeout(() => setTgSaved(false), 3000);
     } catch (e) {
-      setTgError(e?.message || 'Failed to save.');
+      setTgError(typeof e === 'string' ? e : (e?.message || 'Failed to save.'));
     }
     setTgSaving(false);
   }
@@ -726,6 +830,152 @@ export default function Settings() {
 
         </div>{/* end grey-out wrapper */}
 
+        {keystore && (keystore.exists || keystore.plaintextKey) && (
+          <div className="card" style={{ marginBottom: 16 }}>
+            <div className="card-title">Private Key</div>
+            {keystore.exists ? (
+              <>
+                <div className="card-row">
+                  <span className="card-label">Encrypted key store</span>
+                  <span className="card-value">{keystore.unlocked ? 'Unlocked' : 'Locked'}</span>
+                </div>
+                {keystore.plaintextKey && <div className="warning-msg">A plaintext copy of the key is still in the data folder.</div>}
+                <div className="form-group">
+                  <label className="form-label">Current passphrase</label>
+                  <input className="form-input" type="password" value={ksCurrent} onChange={e => setKsCurrent(e.target.value)} />
+                </div>
+                <div className="form-group">
+                  <label className="form-label">New passphrase <Tip text="At least 8 characters. There is no way to recover a forgotten passphrase." /></label>
+                  <input className="form-input" type="password" value={ksNew} onChange={e => setKsNew(e.target.value)} />
+                </div>
+                <div style={{ display: 'flex', gap: 8, flexWrap: 'wrap' }}>
+                  <button className="btn btn-outline" disabled={ksBusy || !ksCurrent || !ksNew} onClick={() => handleKeystore(() => changeKeystorePassphrase(ksCurrent, ksNew), 'Passphrase changed')}>Change Passphrase</button>
+                  {keystore.outdated && (
+                    <button className="btn btn-outline" disabled={ksBusy || !ksCurrent} onClick={() => handleKeystore(() => reencryptKeystore(ksCurrent), 'Key store upgraded')}>Upgrade Encryption</button>
+                  )}
+                  {keystore.unlocked && (
+                    <button className="btn btn-outline" disabled={ksBusy} onClick={() => handleKeystore(lockKeystore, 'Locked')}>Lock</button>
+                  )}
+                </div>
+              </>
+            ) : (
+              <>
+                <div className="warning-msg">Your private key is stored unencrypted. Encrypt it with a passphrase; the plaintext copy is then deleted.</div>
+                <div className="form-group">
+                  <label className="form-label">New passphrase</label>
+                  <input className="form-input" type="password" placeholder="At least 8 characters" value={ksNew} onChange={e => setKsNew(e.target.value)} />
+                </div>
+                <button className="btn btn-primary" disabled={ksBusy || !ksNew} onClick={() => handleKeystore(() => createKeystore(ksNew), 'Key encrypted')}>Encrypt Key</button>
+              </>
+            )}
+            {ksMsg && <div className="success-msg" style={{ marginTop: 8 }}>{ksMsg}</div>}
+            {ksError && <div className="error-msg" style={{ marginTop: 8 }}>{ksError}</div>}
+          </div>
+        )}
+
+        <div className="card" style={{ marginBottom: 16 }}>
+          <div className="card-title">File Permissions</div>
+          {audit && !audit.supported && <div className="form-hint">Permissions are only checked on macOS and Linux.</div>}
+          {audit && audit.supported && audit.items.filter(i => i.severity !== 'ok').length === 0 && (
+            <div className="success-msg">All {audit.items.length} sensitive files and folders are private to your user.</div>
+          )}
+          {audit && audit.items.filter(i => i.severity !== 'ok').map(item => (
+            <div key={item.path} className="card-row">
+              <span className="card-label mono" style={{ wordBreak: 'break-all' }}>{item.path}</span>
+              <span className={item.severity === 'critical' ? 'error-msg' : 'warning-msg'}>{item.issues.join('; ')}</span>
+            </div>
+          ))}
+          <div style={{ display: 'flex', gap: 8, marginTop: 8 }}>
+            <button className="btn btn-outline" onClick={() => runAudit(false)} disabled={auditBusy}>{auditBusy ? 'Checking...' : 'Check Permissions'}</button>
+            {audit && audit.items.some(i => i.severity !== 'ok' && i.fixable) && (
+              <button className="btn btn-primary" onClick={() => runAudit(true)} disabled={auditBusy}>Fix Permissions</button>
+            )}
+          </div>
+          {auditError && <div className="error-msg" style={{ marginTop: 8 }}>{auditError}</div>}
+        </div>
+
+        <div className="card" style={{ marginBottom: 16 }}>
+          <div className="card-title">Audit Log</div>
+          <div className="form-hint">Every start, stop, position close and write to config or secrets, chained so edits to the log can be detected.</div>
+          {auditChain && (auditChain.valid ? (
+            <div className="success-msg" style={{ marginTop: 8 }}>Chain intact: {auditChain.entries} entries.</div>
+          ) : (
+            <div className="error-msg" style={{ marginTop: 8 }}>Audit log was tampered with at line {auditChain.brokenAtLine}: {auditChain.problem}</div>
+          ))}
+          {auditLog && auditLog.length > 0 && (
+            <div className="table-wrap" style={{ marginTop: 8, maxHeight: 320, overflowY: 'auto' }}>
+              <table>
+                <thead>
+                  <tr><th>Time</th><th>Action</th><th>By</th><th>Details</th><th>Result</th></tr>
+                </thead>
+                <tbody>
+                  {auditLog.map(e => (
+                    <tr key={e.seq}>
+                      <td>{new Date(e.ts).toLocaleString()}</td>
+                      <td className="mono">{e.action}</td>
+                      <td>{e.actor}</td>
+                      <td className="mono" style={{ fontSize: 11, wordBreak: 'break-all' }}>{JSON.stringify(e.params)}</td>
+                      <td>{e.outcome === 'ok' ? 'OK' : <span style={{ color: 'var(--red)' }}>{e.error}</span>}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+          <button className="btn btn-outline" onClick={loadAuditLog} style={{ marginTop: 8 }}>{auditLog ? 'Refresh' : 'Show Audit Log'}</button>
+          {auditLogError && <div className="error-msg" style={{ marginTop: 8 }}>{auditLogError}</div>}
+        </div>
+
+        <div className="card" style={{ marginBottom: 16 }}>
+          <div className="card-title">Config Reload</div>
+          <div className="form-group">
+            <label className="form-label">When config.json changes while the bot is running <Tip text="The bot only reads its config on start. Edits made here or in a text editor are checked first — an invalid edit is never applied." /></label>
+            <select className="form-input" value={reloadPolicy} onChange={e => handleReloadPolicy(e.target.value)}>
+              <option value="prompt">Ask me</option>
+              <option value="restart">Restart immediately</option>
+              <option value="restartWhenFlat">Restart when flat</option>
+              <option value="ignore">Do nothing (apply on next start)</option>
+            </select>
+          </div>
+        </div>
+
+        {licenseNet && (
+          <div className="card" style={{ marginBottom: 16 }}>
+            <div className="card-title">License Server</div>
+            <div className="form-group">
+              <label className="form-label">Endpoints <Tip text="One validate URL per line, tried in order until one answers. Only add servers you trust." /></label>
+              <textarea className="form-input" rows={3} value={licenseNet.endpoints} onChange={e => setLicenseNet({ ...licenseNet, endpoints: e.target.value })} style={{ fontFamily: 'monospace', fontSize: 12 }} />
+            </div>
+            <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr 1fr', gap: 12 }}>
+              <div className="form-group">
+                <label className="form-label">Timeout (seconds)</label>
+                <input className="form-input" type="number" min={1} max={60} value={licenseNet.timeoutSecs} onChange={e => setLicenseNet({ ...licenseNet, timeoutSecs: e.target.value })} />
+              </div>
+              <div className="form-group">
+                <label className="form-label">Retries per endpoint</label>
+                <input className="form-input" type="number" min={0} max={5} value={licenseNet.retries} onChange={e => setLicenseNet({ ...licenseNet, retries: e.target.value })} />
+              </div>
+              <div className="form-group">
+                <label className="form-label">Retry delay (ms) <Tip text="Doubles after each failed attempt." /></label>
+                <input className="form-input" type="number" min={0} max={30000} value={licenseNet.retryDelayMs} onChange={e => setLicenseNet({ ...licenseNet, retryDelayMs: e.target.value })} />
+              </div>
+            </div>
+            <div className="form-group">
+              <label className="form-label">HTTP(S) proxy <Tip text="e.g. http://proxy.corp:8080. Leave empty to use the HTTP_PROXY / HTTPS_PROXY environment variables, if set." /></label>
+              <input className="form-input" type="text" placeholder="http://host:port" value={licenseNet.proxy} onChange={e => setLicenseNet({ ...licenseNet, proxy: e.target.value })} />
+            </div>
+            <div className="form-group">
+              <label className="form-label">Bypass proxy for</label>
+              <input className="form-input" type="text" placeholder="localhost,127.0.0.1" value={licenseNet.noProxy} onChange={e => setLicenseNet({ ...licenseNet, noProxy: e.target.value })} />
+            </div>
+            <div style={{ display: 'flex', alignItems: 'center', gap: 12 }}>
+              <button className="btn btn-primary" onClick={handleSaveLicenseNet} disabled={licBusy}>{licBusy ? 'Checking...' : 'Save & Check'}</button>
+              {licMsg && <span className="success-msg">{licMsg}</span>}
+            </div>
+            {licError && <div className="error-msg" style={{ marginTop: 8 }}>{licError}</div>}
+          </div>
+        )}
+
         <div className="card" style={{ borderColor: 'rgba(248, 113, 113, 0.2)', background: 'rgba(248, 113, 113, 0.04)' }}>
           <div className="card-title" style={{ color: 'var(--red)' }}>Danger Zone</div>
           <button className="btn btn-outline" onClick={handleReset} style={{ borderColor: 'var(--red)', color: 'var(--red)' }}>Reset All Settings &amp; Re-run Setup</button>
@@ -748,10 +998,10 @@ export default function Settings() {
           <div style={{ display: 'flex', alignItems: 'center', gap: 12 }}>
             <div style={{
               width: 10, height: 10, borderRadius: '50%',
-              background: tgEnabled && tgToken ? 'var(--green)' : 'var(--text-muted)',
+              background: tgEnabled && (tgToken || tgTokenSaved) ? 'var(--green)' : 'var(--text-muted)',
             }} />
             <span style={{ fontWeight: 600, fontSize: 15 }}>
-              {tgEnabled && tgToken ? 'Telegram pings enabled' : 'Telegram pings disabled'}
+              {tgEnabled && (tgToken || tgTokenSaved) ? 'Telegram pings enabled' : 'Telegram pings disabled'}
             </span>
           </div>
           <div style={{ marginTop: 12 }}>
@@ -788,7 +1038,7 @@ export default function Settings() {
               <input
                 className="form-input mono"
                 type={showToken ? 'text' : 'password'}
-                placeholder="123456789:AAHdqTc..."
+                placeholder={tgTokenSaved ? 'Saved — enter a new token to replace it' : '123456789:AAHdqTc...'}
                 value={tgToken}
                 onChange={e => setTgToken(e.target.value)}
                 style={{ flex: 1 }}
@@ -803,7 +1053,7 @@ export default function Settings() {
             </div>
             {tgToken && !showToken && (
               <div className="form-hint" style={{ marginTop: 4 }}>
-                Current: {maskToken(tgToken)}
+                Entered: {maskToken(tgToken)}
               </div>
             )}
           </div>
@@ -817,7 +1067,7 @@ export default function Settings() {
             />
           </div>
           <div style={{ display: 'flex', gap: 8, marginTop: 8 }}>
-            <button className="btn btn-outline" onClick={handleTgTest} disabled={!tgToken || !tgChat}>
+            <button className="btn btn-outline" onClick={handleTgTest} disabled={(!tgToken && !tgTokenSaved) || !tgChat}>
               Send Test Message
             </button>
             {tgTestMsg && (
@@ -1,11 +1,15 @@
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { createKeystore, deleteKeystore } from '../lib/keystore';
 
 export default function Setup({ onComplete }) {
   const [step, setStep] = useState(1);
   const [wallet, setWallet] = useState('');
   const [pkMethod, setPkMethod] = useState('file');
   const [privateKey, setPrivateKey] = useState('');
+  const [passphrase, setPassphrase] = useState('');
+  const [passphraseConfirm, setPassphraseConfirm] = useState('');
+  const [keyCheck, setKeyCheck] = useState(null);
   const [tgEnabled, setTgEnabled] = useState(false);
   const [tgToken, setTgToken] = useState('');
   const [tgChat, setTgChat] = useState('');
@@ -28,11 +32,33 @@ export default function Setup({ onComplete }) {
     return true;
   }
 
-  function validateKey() {
+  async function validateKey() {
     if (!privateKey.trim()) {
       setError('Private key is required.');
       return false;
     }
+    if (pkMethod === 'file') {
+      if (passphrase.length < 8) {
+        setError('Passphrase must be at least 8 characters.');
+        return false;
+      }
+      if (passphrase !== passphraseConfirm) {
+        setError('Passphrases do not match.');
+        return false;
+      }
+    }
+    // Catch an agent key paired with the wrong account before anything is saved
+    try {
+      const result = await invoke('check_private_key', { privateKey: privateKey.trim(), address: wallet.trim(), checkAgent: true });
+      if (result.kind === 'agent' && result.agentApproved === false) {
+        setError(result.message);
+        return false;
+      }
+      setKeyCheck(result);
+    } catch (e) {
+      setError(typeof e === 'string' ? e : e?.message || 'Invalid private key.');
+      return false;
+    }
     setError('');
     return true;
   }
@@ -94,30 +120,41 @@ export default function Setup({ onComplete }) {
       const keyPath = `${configDir}/private_key`;
       const tgTokenPath = `${configDir}/tg_token`;
 
-      // Update config to use actual absolute paths
-      config.wallet.privateKeyPath = keyPath;
+      // Update config to use actual absolute paths. With the key store the app hands
+      // the decrypted key to the bot, so there is no key file to point at.
+      if (pkMethod === 'file') delete config.wallet.privateKeyPath;
+      else config.wallet.privateKeyPath = keyPath;
       if (tgEnabled) config.telegram.tokenPath = tgTokenPath;
 
       // Write config.json and .env (with restrictive perms via Rust)
       await invoke('write_bot_file', { filename: 'config.json', contents: JSON.stringify(config, null, 2) });
 
-      const envLines = [
-        '# HL Signalbot - generated by setup wizard',
-        '',
-        `HL_WALLET_ADDRESS=${wallet.trim()}`,
-        pkMethod === 'env' ? `HL_PRIVATE_KEY=${privateKey.trim()}` : `HL_PRIVATE_KEY_PATH=${keyPath}`,
-        '',
-        `TG_ENABLED=${tgEnabled}`,
-        tgEnabled ? `TG_CHAT=${tgChat.trim()}` : '',
-        tgEnabled ? `TG_TOKEN_PATH=${tgTokenPath}` : '',
-        '',
-      ].filter(l => l !== undefined).join('\n');
-      await invoke('write_bot_file', { filename: '.env', contents: envLines });
+      const envSet = {
+        HL_WALLET_ADDRESS: wallet.trim(),
+        TG_ENABLED: String(tgEnabled),
+      };
+      const envUnset = ['TG_TOKEN', 'HL_PRIVATE_KEY_PATH'];
+      if (pkMethod === 'env') {
+        envSet.HL_PRIVATE_KEY = privateKey.trim();
+      } else {
+        envUnset.push('HL_PRIVATE_KEY');
+      }
+      if (tgEnabled) {
+        envSet.TG_CHAT = tgChat.trim();
+        envSet.TG_TOKEN_PATH = tgTokenPath;
+      } else {
+        envUnset.push('TG_CHAT', 'TG_TOKEN_PATH');
+      }
+      await invoke('update_env', { set: envSet, unset: envUnset });
 
-      // Write secret files with restrictive permissions (600 on Unix)
+      // Encrypt the key with the passphrase; the key store starts unlocked. A key
+      // store left from an earlier setup would override the key just put in .env.
       if (pkMethod === 'file' && privateKey.trim()) {
-        await invoke('write_secret_file', { path: keyPath, contents: privateKey.trim() + '\n' });
+        await createKeystore(passphrase, privateKey.trim(), true);
+      } else if (pkMethod === 'env') {
+        await deleteKeystore();
       }
+      // Write secret files with restrictive permissions (600 on Unix)
       if (tgEnabled && tgToken.trim()) {
         await invoke('write_secret_file', { path: tgTokenPath, contents: tgToken.trim() + '\n' });
       }
@@ -126,7 +163,7 @@ export default function Setup({ onComplete }) {
 
       onComplete();
     } catch (e) {
-      setError(e?.message || 'Failed to save configuration.');
+      setError(typeof e === 'string' ? e : e?.message || 'Failed to save configuration.');
     }
     setSaving(false);
   }
@@ -165,11 +202,20 @@ export default function Setup({ onComplete }) {
               <label className="form-label">Private key (hex)</label>
               <input className="form-input mono" type="password" placeholder="64-character hex string" value={privateKey} onChange={e => setPrivateKey(e.target.value)} />
               <div className="form-hint">Find it in MetaMask: Settings &gt; Security &gt; Reveal Private Key</div>
-              {error && <div className="error-msg">{error}</div>}
+              {pkMethod !== 'file' && error && <div className="error-msg">{error}</div>}
             </div>
+            {pkMethod === 'file' && (
+              <div className="form-group">
+                <label className="form-label">Passphrase</label>
+                <input className="form-input" type="password" placeholder="At least 8 characters" value={passphrase} onChange={e => setPassphrase(e.target.value)} />
+                <input className="form-input" type="password" placeholder="Repeat passphrase" value={passphraseConfirm} onChange={e => setPassphraseConfirm(e.target.value)} style={{ marginTop: 8 }} />
+                <div className="form-hint">Needed each time you open the app to start the bot. It cannot be recovered — if you forget it, re-run setup with your key.</div>
+                {error && <div className="error-msg">{error}</div>}
+              </div>
+            )}
             <div className="wizard-actions">
               <button className="btn btn-outline" onClick={() => setStep(1)}>Back</button>
-              <button className="btn btn-primary" onClick={() => validateKey() && setStep(3)}>Next</button>
+              <button className="btn btn-primary" onClick={async () => (await validateKey()) && setStep(3)}>Next</button>
             </div>
           </>
         )}
@@ -178,6 +224,9 @@ export default function Setup({ onComplete }) {
           <>
             <h2>Fund Your Account</h2>
             <p className="step-desc">The bot needs USDC on Hyperliquid to trade. Deposit before starting.</p>
+            {keyCheck && (
+              <div className={keyCheck.kind === 'master' || keyCheck.agentApproved ? 'success-msg' : 'warning-msg'} style={{ marginBottom: 12 }}>{keyCheck.message}</div>
+            )}
             <div className="info-box">
               <strong>How to deposit:</strong><br />
               1. Go to <a href="https://app.hyperliquid.xyz" target="_blank" rel="noreferrer">app.hyperliquid.xyz</a> and connect your wallet<br />
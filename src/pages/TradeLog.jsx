@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
-import { readConfig } from '../lib/config';
+import { listen } from '@tauri-apps/api/event';
+import { readConfig, readTradeLog, getPnlByExitReason } from '../lib/config';
 
 function mergeSubFills(fills) {
   const sorted = [...fills].sort((a, b) => Number(a.time) - Number(b.time));
@@ -91,17 +92,48 @@ function buildPositions(fills) {
   return positions;
 }
 
+// Closed trades as the bot recorded them, for when Hyperliquid can't be reached
+async function localTrades() {
+  const records = await readTradeLog({ action: 'close', limit: 500 });
+  return records.map(r => ({
+    side: r.side,
+    entryPx: r.entryPx,
+    exitPx: r.exitPx,
+    sizeBtc: r.sizeBtc,
+    pnlUsd: (r.hlPnl ?? r.pnlUsd ?? 0) - (r.hlFee ?? 0),
+    ts: r.ts,
+    isLive: false,
+    exitReason: r.exitReason,
+  }));
+}
+
+const REASON_LABELS = { stop_out: 'Stop loss', runner_exit: 'Runner exit', external_close: 'Closed outside the bot', manual: 'Manual close' };
+
+function reasonLabel(reason) {
+  if (REASON_LABELS[reason]) return REASON_LABELS[reason];
+  const tp = /^tp(\d+)$/.exec(reason || '');
+  return tp ? `Take profit ${tp[1]}` : reason;
+}
+
 export default function TradeLog() {
   const [trades, setTrades] = useState([]);
   const [loading, setLoading] = useState(true);
+  const [offline, setOffline] = useState(false);
+  const [reasons, setReasons] = useState([]);
 
   useEffect(() => {
     loadTrades();
     const interval = setInterval(loadTrades, 15000);
-    return () => clearInterval(interval);
+    // New record in trades.jsonl — give HL a moment to index the fill, then refresh
+    const unlistenTrade = listen('trade-appended', () => setTimeout(loadTrades, 2000));
+    return () => {
+      clearInterval(interval);
+      unlistenTrade.then(fn => fn());
+    };
   }, []);
 
   async function loadTrades() {
+    getPnlByExitReason().then(setReasons).catch(() => setReasons([]));
     try {
       const cfg = await readConfig();
       const wallet = cfg?.wallet?.address;
@@ -167,9 +199,11 @@ export default function TradeLog() {
       }
 
       setTrades(rows);
+      setOffline(false);
     } catch (err) {
       console.error('[TradeLog] Failed to load from HL:', err);
-      setTrades([]);
+      setTrades(await localTrades());
+      setOffline(true);
     }
     setLoading(false);
   }
@@ -209,7 +243,35 @@ export default function TradeLog() {
         </div>
       </div>
 
+      {reasons.length > 0 && (
+        <div className="card" style={{ marginBottom: 20 }}>
+          <div className="card-title">PnL by Exit Reason</div>
+          <div className="table-wrap">
+            <table>
+              <thead>
+                <tr><th>Reason</th><th>Closes</th><th>Wins / Losses</th><th>PnL</th></tr>
+              </thead>
+              <tbody>
+                {reasons.map(r => (
+                  <tr key={r.exitReason}>
+                    <td>{reasonLabel(r.exitReason)}</td>
+                    <td className="mono">{r.closes}</td>
+                    <td className="mono"><span className="text-green">{r.wins}</span> / <span className="text-red">{r.losses}</span></td>
+                    <td className={`mono ${r.pnlUsd >= 0 ? 'text-green' : 'text-red'}`}>{r.pnlUsd >= 0 ? '+' : ''}${r.pnlUsd.toFixed(2)}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        </div>
+      )}
+
       <div className="card">
+        {offline && (
+          <div className="text-muted" style={{ fontSize: 12, marginBottom: 8 }}>
+            Hyperliquid is unreachable — showing closes recorded by the bot.
+          </div>
+        )}
         <div className="table-wrap">
           <table>
             <thead>
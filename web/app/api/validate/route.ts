@@ -1,11 +1,13 @@
 import { NextRequest, NextResponse } from "next/server";
 import { validateKey } from "@/lib/license";
+import { signLicenseToken } from "@/lib/license-token";
 
 export async function POST(req: NextRequest) {
   try {
     const { key } = await req.json();
     const valid = await validateKey(key);
-    return NextResponse.json({ valid });
+    if (!valid) return NextResponse.json({ valid });
+    return NextResponse.json({ valid, plan: "standard", token: signLicenseToken(key) });
   } catch {
     return NextResponse.json({ valid: false }, { status: 400 });
   }